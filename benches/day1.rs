@@ -0,0 +1,78 @@
+// Times the day 1 decoder on generated multi-megabyte inputs, compared to the per-suffix regex
+// matching it replaced.  Run with `cargo bench --bench day1`.
+
+use std::hint::black_box;
+use std::thread::available_parallelism;
+use std::time::{Duration, Instant};
+
+use advent_of_code::day1::{sum_calibration_values, sum_stream, Decoder, Mode, DEFAULT_CHUNK_SIZE};
+use advent_of_code::rng::Rng;
+use regex::Regex;
+
+const DIGIT_NAMES: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const ROUNDS: usize = 5;
+
+/// Lines of random letters with digits and digit names mixed in, some of them overlapping.
+fn generate_input(rng: &mut Rng, size: usize, line_length: usize) -> String {
+    let mut input = String::with_capacity(size + line_length * 2);
+    while input.len() < size {
+        let line_start = input.len();
+        input.push(char::from(b'0' + rng.range(0..10) as u8));
+        while input.len() - line_start < line_length {
+            match rng.range(0..10) {
+                0 => input.push(char::from(b'0' + rng.range(0..10) as u8)),
+                1 | 2 => input.push_str(DIGIT_NAMES[rng.range(0..10)]),
+                _ => input.push(char::from(b'a' + rng.range(0..26) as u8)),
+            }
+        }
+        input.push('\n');
+    }
+    input
+}
+
+fn regex_sum(input: &str) -> usize {
+    let names = DIGIT_NAMES.iter().enumerate().flat_map(|(index, name)| [name.to_string(), index.to_string()]);
+    let regex = Regex::new(&format!("^({})", names.collect::<Vec<String>>().join("|"))).unwrap();
+    let value = |token: &str| match DIGIT_NAMES.iter().position(|name| *name == token) {
+        Some(index) => index,
+        None => token.parse().unwrap(),
+    };
+    input
+        .lines()
+        .map(|line| {
+            let mut digits = (0..line.len()).filter_map(|start| regex.find(&line[start..]).map(|found| value(found.as_str())));
+            // Like the old decoder, try every suffix rather than searching from the end.
+            let first = digits.next().unwrap();
+            first * 10 + digits.fold(first, |_, digit| digit)
+        })
+        .sum()
+}
+
+fn time(name: &str, input: &str, solve: impl Fn(&str) -> usize) -> usize {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        result = black_box(solve(black_box(input)));
+        best = best.min(start.elapsed());
+    }
+    let megabytes = input.len() as f64 / 1e6;
+    println!("  {name:<10} {:>8.1} ms {:>8.1} MB/s", best.as_secs_f64() * 1e3, megabytes / best.as_secs_f64());
+    result
+}
+
+fn main() {
+    let mut rng = Rng::new(2023);
+    for (size, line_length) in [(4_000_000, 40), (4_000_000, 400), (16_000_000, 60)] {
+        let input = generate_input(&mut rng, size, line_length);
+        println!("{} MB, {} lines of {line_length} bytes", input.len() / 1_000_000, input.lines().count());
+        let scanner = time("scanner", &input, |input| sum_calibration_values(input, Mode::Spelled));
+        let workers = available_parallelism().map_or(1, usize::from);
+        let decoder = Decoder::new(Mode::Spelled);
+        let stream = time("stream", &input, |input| {
+            sum_stream(input.as_bytes(), &decoder, workers, DEFAULT_CHUNK_SIZE).unwrap()
+        });
+        let regex = time("regex", &input, regex_sum);
+        assert_eq!((scanner, stream), (regex, regex));
+    }
+}
@@ -0,0 +1,58 @@
+// Times the day 2 game parser on millions of generated game lines, compared to the regex based
+// parser it replaced.  Run with `cargo bench --bench day2`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code::day2::{default_bag, generate_games, parse_games, part_one, part_two};
+use regex::Regex;
+
+const ROUNDS: usize = 3;
+
+/// The parser before it was replaced, which compiles its regular expressions for every line.
+fn regex_parse(line: &str) -> (usize, Vec<HashMap<String, usize>>) {
+    let captures = Regex::new(r"^Game (\d+): (.*)$").unwrap().captures(line).unwrap();
+    let draws = Regex::new("; *")
+        .unwrap()
+        .split(&captures[2])
+        .map(|draw| {
+            let split_draw = Regex::new(", *").unwrap();
+            let cubes = split_draw.split(draw).map(|cube| {
+                let (count, color) = cube.split_once(' ').unwrap();
+                (color.to_lowercase(), count.parse().unwrap())
+            });
+            cubes.collect()
+        })
+        .collect();
+    (captures[1].parse().unwrap(), draws)
+}
+
+fn time(name: &str, input: &str, solve: impl Fn(&str) -> usize) -> usize {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        result = black_box(solve(black_box(input)));
+        best = best.min(start.elapsed());
+    }
+    let lines = input.lines().count() as f64;
+    println!("  {name:<10} {:>8.1} ms {:>10.0} lines/s", best.as_secs_f64() * 1e3, lines / best.as_secs_f64());
+    result
+}
+
+fn main() {
+    let log = generate_games(2, &default_bag(), 2_000_000, 1..7, 0.05);
+    let input = log.input;
+    println!("{} MB, {} games", input.len() / 1_000_000, input.lines().count());
+    time("parse", &input, |input| parse_games(input).count());
+    let part_one = time("part one", &input, part_one);
+    let part_two = time("part two", &input, part_two);
+    assert_eq!((part_one, part_two), (log.id_sum, log.power_sum));
+
+    let sample: String = input.lines().take(20_000).map(|line| format!("{line}\n")).collect();
+    println!("{} games", sample.lines().count());
+    let games = time("parse", &sample, |input| parse_games(input).count());
+    let regex_games = time("regex", &sample, |input| input.lines().map(regex_parse).count());
+    assert_eq!(games, regex_games);
+}
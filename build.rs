@@ -0,0 +1,19 @@
+// Generates the C header for src/ffi.rs into OUT_DIR.  A test in src/ffi.rs compares it with the
+// checked-in include/aoc.h, which is what C programs use.
+
+use std::env;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(Path::new(&manifest_dir).join("cbindgen.toml")).unwrap();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(Path::new(&manifest_dir).join("src/ffi.rs"))
+        .generate()
+        .expect("generating the C header failed")
+        .write_to_file(Path::new(&out_dir).join("aoc.h"));
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
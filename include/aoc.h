@@ -0,0 +1,49 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The puzzle was solved and the answer has been stored.
+#define AOC_OK 0
+
+// There is no solver for the requested day and part.
+#define AOC_UNKNOWN_PUZZLE 1
+
+// A required pointer argument was null.
+#define AOC_NULL_ARGUMENT 2
+
+// The input is not valid UTF-8.
+#define AOC_INVALID_INPUT 3
+
+// The solver panicked, usually because the input does not have the expected format.
+#define AOC_SOLVER_FAILED 4
+
+// Solves `part` of the puzzle for `day` using the `input_len` bytes at `input`.  On success,
+// `*answer` is set to a newly allocated, NUL-terminated string that must be released with
+// `aoc_free_string`.  On failure, `*answer` is set to NULL and an error code is returned.
+// Nothing is written to stdout or stderr.
+//
+// # Safety
+//
+// `input` must point to `input_len` readable bytes (it may be NULL if `input_len` is zero) and
+// `answer` must point to writable storage for one pointer.
+int32_t aoc_solve(uint32_t day,
+                  uint32_t part,
+                  const uint8_t *input,
+                  size_t input_len,
+                  char **answer);
+
+// Releases an answer string returned by `aoc_solve`.  Passing NULL is a no-op.
+//
+// # Safety
+//
+// `answer` must be NULL or a string returned by `aoc_solve` that has not been released yet.
+void aoc_free_string(char *answer);
+
+#endif  /* AOC_H */
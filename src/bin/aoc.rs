@@ -0,0 +1,61 @@
+use std::env;
+use std::fs::{read_to_string, write};
+use std::process::exit;
+use advent_of_code::{check, repl};
+
+const USAGE: &str = "\
+usage: aoc repl <day> [<file>]
+       aoc check <day> <file> [--fix]
+
+The input file for repl defaults to data/day<day>.dat.  check reports format
+problems in an input file; --fix rewrites it with normalised whitespace and
+blank lines.  Day 1 digit names are only recognised in English.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        ["repl", day] => repl_command(day, &format!("data/day{day}.dat")),
+        ["repl", day, filename] => repl_command(day, filename),
+        ["check", day, filename] => check_command(day, filename, false),
+        ["check", day, filename, "--fix"] => check_command(day, filename, true),
+        _ => {
+            eprintln!("{USAGE}");
+            exit(2)
+        }
+    };
+    if let Err(error) = result {
+        eprintln!("aoc: {error}");
+        exit(1)
+    }
+}
+
+fn parse_day(day: &str) -> Result<usize, String> {
+    day.parse().map_err(|_| format!("invalid day {day:?}"))
+}
+
+fn repl_command(day: &str, filename: &str) -> Result<(), String> {
+    repl::run(parse_day(day)?, filename)
+}
+
+fn check_command(day: &str, filename: &str, fix: bool) -> Result<(), String> {
+    let day = parse_day(day)?;
+    let mut input = read_to_string(filename).map_err(|error| format!("{filename}: {error}"))?;
+    let normalised = check::normalise(day, &input);
+    if fix && normalised != input {
+        write(filename, &normalised).map_err(|error| format!("{filename}: {error}"))?;
+        println!("{filename}: rewritten with normalised whitespace");
+        input = normalised.clone();
+    }
+    let problems = check::check(day, &input).ok_or(format!("there is no solver for day {day}"))?;
+    for problem in &problems {
+        println!("{filename}:{}: {}", problem.line, problem.message);
+    }
+    if problems.is_empty() {
+        println!("{filename}: ok");
+        return Ok(());
+    }
+    if normalised != input {
+        println!("{filename}: run with --fix to normalise whitespace");
+    }
+    Err(format!("{} problem(s) found", problems.len()))
+}
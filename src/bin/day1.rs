@@ -1,71 +1,85 @@
 use std::env;
-use std::fs::read_to_string;
-use regex::Regex;
-use std::collections::HashMap;
-use memoize::memoize;
+use std::fs::{read_to_string, File};
+use std::io::stdout;
+use std::process::exit;
+use std::thread::available_parallelism;
+use advent_of_code::day1::{sum_stream, write_csv, write_table, Decoder, Mode, Vocabulary, DEFAULT_CHUNK_SIZE};
 
-const DIGIT_NAMES: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const USAGE: &str = "\
+usage: day1 [--vocabulary <language or file>] [--phrases] [--ignore-case] [--threads <n>] <file>
+       day1 [...] --explain <1|2|phrases> [--csv] <file>";
 
-#[memoize]
-fn digit_encoding() -> (Regex, HashMap<String, usize>) {
-    let mut map: HashMap<String, usize> = HashMap::new();
-    let mut regex_parts = Vec::new();
-
-    for (index, name) in DIGIT_NAMES.iter().enumerate() {
-        map.insert(name.to_string(), index);
-        map.insert(index.to_string(), index);
-
-        regex_parts.push(name.to_string());
-        regex_parts.push(index.to_string());
-    }
-
-    let regex = format!("^({})", regex_parts.join("|"));
-    println!("regex: {}", regex);
-
-    (Regex::new(&regex).unwrap(), map)
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    exit(2)
 }
 
-fn extract_calibration_value(string: String) -> usize {
-    let (regex, map) = digit_encoding();
-
-    let mut found_digit = false;
-    let mut digit1: usize = 0;
-    let mut digit2: usize = 0;
-
-    let mut s: &str = &string;
-    while !s.is_empty() {
-        if let Some(mat) = regex.find(s) {
-            let value = *map.get(mat.as_str()).unwrap();
-            if !found_digit {
-                digit1 = value;
-                digit2 = value;
-                found_digit = true;
-            } else {
-                digit2 = value;
+fn main() {
+    let mut vocabulary = Vocabulary::english();
+    let mut phrases = false;
+    let mut ignore_case = false;
+    let mut explain = None;
+    let mut csv = false;
+    let mut threads = available_parallelism().map_or(1, usize::from);
+    let mut filename = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vocabulary" => {
+                let name = args.next().unwrap_or_else(|| usage());
+                vocabulary = Vocabulary::builtin(&name)
+                    .map_or_else(|| Vocabulary::load(&name), Ok)
+                    .unwrap_or_else(|error| {
+                        eprintln!("day1: {error}");
+                        exit(1)
+                    });
             }
+            "--phrases" => phrases = true,
+            "--ignore-case" => ignore_case = true,
+            "--explain" => explain = Some(args.next().unwrap_or_else(|| usage())),
+            "--csv" => csv = true,
+            "--threads" => {
+                threads = match args.next().map(|threads| threads.parse()) {
+                    Some(Ok(threads)) if threads > 0 => threads,
+                    _ => usage(),
+                }
+            }
+            _ if filename.is_none() && !arg.starts_with("--") => filename = Some(arg),
+            _ => usage(),
         }
-        s = &s[1..];
     }
-    if !found_digit {
-        panic!("could not find digit in input line \"{string}\"")
+    let filename = filename.unwrap_or_else(|| usage());
+    let decoder = |decoder: Decoder| if ignore_case { decoder.ignoring_case() } else { decoder };
+    if let Some(part) = explain {
+        let decoder = match part.as_str() {
+            "1" => decoder(Decoder::new(Mode::Digits)),
+            "2" => decoder(Decoder::with_vocabulary(&vocabulary)),
+            "phrases" => decoder(Decoder::new(Mode::Phrases)),
+            _ => usage(),
+        };
+        let input = read_to_string(&filename).unwrap_or_else(|error| {
+            eprintln!("day1: {filename}: {error}");
+            exit(1)
+        });
+        let explanations = decoder.explain(&input);
+        let write = if csv { write_csv } else { write_table };
+        write(&explanations, &mut stdout().lock()).unwrap();
+        return;
+    }
+    // The input is streamed rather than read at once, as it can be larger than the memory.
+    let sum = |decoder: Decoder| {
+        let file = File::open(&filename).unwrap_or_else(|error| {
+            eprintln!("day1: {filename}: {error}");
+            exit(1)
+        });
+        sum_stream(file, &decoder, threads, DEFAULT_CHUNK_SIZE).unwrap_or_else(|error| {
+            eprintln!("day1: {filename}: {error}");
+            exit(1)
+        })
+    };
+    println!("part 1 sum: {}", sum(Decoder::new(Mode::Digits)));
+    println!("part 2 sum: {}", sum(decoder(Decoder::with_vocabulary(&vocabulary))));
+    if phrases {
+        println!("number phrase sum: {}", sum(decoder(Decoder::new(Mode::Phrases))));
     }
-
-    let value_string = format!("{digit1}{digit2}");
-    println!("value: {value_string}");
-    value_string.parse::<usize>().unwrap()
-}
-
-fn read_lines(filename: &str) -> usize {
-    read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .map(extract_calibration_value)
-        .sum()
-}
-
-fn main() {
-    let filename = env::args().nth(1).unwrap();
-    let sum = read_lines(&filename);
-    println!("sum: {sum}");
 }
@@ -1,157 +1,106 @@
 use std::env;
 use std::fs::read_to_string;
-use regex::Regex;
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::str::FromStr;
-
-#[macro_use]
-extern crate partial_application;
-extern crate strum;
-#[macro_use] extern crate strum_macros;
-
-#[derive(Debug, Hash, Eq, PartialEq, Clone, EnumString)]
-#[strum(ascii_case_insensitive)]
-enum Color {
-    Red,
-    Green,
-    Blue
-}
-
-#[derive(Debug)]
-struct Game {
-    id: usize,
-    draws: Vec<HashMap<Color, usize>>,
-}
-
-fn parse_draw(input_string: &str) -> HashMap<Color, usize> {
-    let split_draw = Regex::new(", *").unwrap();
-    split_draw.split(&input_string)
-        .map(|input_string| input_string.split_whitespace().collect::<Vec<&str>>())
-        .map(|parts|
-            (Color::from_str(parts[1]).unwrap(),
-             parts[0].parse::<usize>().unwrap()))
-        .collect()
-}
-
-fn parse_game(input_string: &str) -> Game {
-    let game_regex = Regex::new(r"^Game (\d+): (.*)$").unwrap();
-    let captures = game_regex.captures(&input_string).unwrap();
-    let id: usize = captures[1].parse().unwrap();
-    let split_draws = Regex::new("; *").unwrap();
-    let draws: Vec<HashMap<Color, usize>> = split_draws.split(&captures[2])
-        .map(parse_draw)
-        .collect();
-    Game {
-        id,
-        draws,
-    }
-}
-
-fn draw_impossible(bag: &HashMap<Color, usize>, draw: &HashMap<Color, usize>) -> bool {
-    draw.iter().any(|(color, count)| bag[color] < *count)
-}
-
-fn game_possible(bag: &HashMap<Color, usize>, game: &Game) -> bool {
-    game.draws.iter()
-        .filter(partial!(draw_impossible => bag, _))
-        .next().is_none()
+use std::process::exit;
+use advent_of_code::day2::{
+    default_bag, estimate_bag, format_bag, game_possible, games_from_json, games_to_json, load_bags, minimal_bag, parse_bag,
+    parse_game, part_one, part_two, possible_games, DrawMode,
+};
+
+const USAGE: &str = "\
+usage: day2 [--bag <colour>=<count>,...]... [--bags <file>] [--without-replacement] <file>
+       day2 [--bag <colour>=<count>,...]... [--without-replacement] --emit <text|json> <file>
+       day2 --infer [--confidence <level>] <file>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    exit(2)
 }
 
-fn part_one(filename: &str) -> usize {
-    let bag: HashMap<Color, usize> = HashMap::from([(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)]);
-    read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(parse_game)
-        .filter(partial!(game_possible => &bag, _))
-        .map(| game | game.id)
-        .sum()
+fn fail(error: String) -> ! {
+    eprintln!("day2: {error}");
+    exit(1)
 }
 
-fn power(game: Game) -> usize {
-    let mut min_counts: HashMap<Color, usize> = HashMap::new();
-    min_counts.insert(Color::Red, 1);
-    min_counts.insert(Color::Green, 1);
-    min_counts.insert(Color::Blue, 1);
-    for draw in game.draws {
-        for color in draw.keys().cloned() {
-            let draw_count = draw[&color];
-            if min_counts[&color] < draw_count {
-                min_counts.insert(color, draw_count);
+fn main() {
+    let mut bags = vec![];
+    let mut filename = None;
+    let mut infer = false;
+    let mut mode = DrawMode::WithReplacement;
+    let mut confidence = 0.95;
+    let mut emit = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bag" => bags.push(parse_bag(&args.next().unwrap_or_else(|| usage())).unwrap_or_else(|error| fail(error))),
+            "--bags" => bags.extend(load_bags(&args.next().unwrap_or_else(|| usage())).unwrap_or_else(|error| fail(error))),
+            "--without-replacement" => mode = DrawMode::WithoutReplacement,
+            "--infer" => infer = true,
+            "--confidence" => {
+                confidence = match args.next().map(|level| level.parse()) {
+                    Some(Ok(level)) if level > 0.0 && level < 1.0 => level,
+                    _ => usage(),
+                }
             }
+            "--emit" => match args.next().as_deref() {
+                Some(format @ ("text" | "json")) => emit = Some(format.to_string()),
+                _ => usage(),
+            },
+            _ if filename.is_none() && !arg.starts_with("--") => filename = Some(arg),
+            _ => usage(),
         }
     }
-    min_counts[&Color::Red] * min_counts[&Color::Green] * min_counts[&Color::Blue]
-}
-
-fn part_two(filename: &str) -> usize {
-    read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(parse_game)
-        .map(power)
-        .sum()
-}
-
-fn main() {
-    let filename = env::args().nth(1).unwrap();
-    let sum = part_one(&filename);
-    println!("Part One - Sum: {sum}");
-    let pow_sum = part_two(&filename);
-    println!("Part Two - Sum: {pow_sum}")
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_game() {
-        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 3 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
-        assert_eq!(game.id, 18);
-        assert_eq!(game.draws.len(), 6);
-        assert_eq!(*game.draws[1].get(&Color::Red).unwrap(), 4);
-    }
-
-    #[test]
-    #[should_panic]
-    fn test_invalid_input() {
-        parse_game("foo");
+    let mut input = read_to_string(filename.unwrap_or_else(|| usage())).unwrap();
+    // Game logs exported with --emit json are read back like the puzzle input.
+    if input.trim_start().starts_with('[') {
+        let games = games_from_json(&input).unwrap_or_else(|error| fail(error));
+        input = games.iter().map(|game| format!("{game}\n")).collect();
     }
-
-    #[test]
-    fn test_game_possible() {
-        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 3 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
-        let bag = HashMap::from([(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)]);
-        assert_eq!(game_possible(&bag, &game), true)
+    if let Some(format) = emit {
+        let games: Vec<_> = input
+            .lines()
+            .map(parse_game)
+            .filter(|game| bags.is_empty() || bags.iter().any(|bag| game_possible(bag, game, mode)))
+            .collect();
+        if format == "json" {
+            print!("{}", games_to_json(&games));
+        } else {
+            games.iter().for_each(|game| println!("{game}"));
+        }
+        return;
     }
-
-    #[test]
-    fn test_game_impossible() {
-        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 30 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
-        let bag = HashMap::from([(Color::Red, 12), (Color::Green, 13), (Color::Blue, 14)]);
-        assert_eq!(game_possible(&bag, &game), false);
+    if infer {
+        let games: Vec<_> = input.lines().map(parse_game).collect();
+        let estimates = estimate_bag(&games, confidence);
+        for (minimum, estimate) in minimal_bag(&games).iter().zip(estimates) {
+            println!(
+                "{}: at least {} (game {}, draw {}), likely {}, at most {} with {}% confidence",
+                minimum.color,
+                minimum.count,
+                minimum.game,
+                minimum.draw,
+                estimate.estimate,
+                estimate.upper_bound,
+                confidence * 100.0
+            );
+        }
+        return;
     }
-
-    #[test]
-    fn test_colors() {
-        assert_eq!(Color::from_str("red").unwrap(), Color::Red);
-        assert_eq!(Color::from_str("green").unwrap(), Color::Green);
-        assert_eq!(Color::from_str("blue").unwrap(), Color::Blue);
+    if bags.is_empty() && mode == DrawMode::WithoutReplacement {
+        bags.push(default_bag());
     }
-
-    #[test]
-    fn test_part_powers() {
-        let sample_data = [
-            ("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green", 48),
-            ("Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue", 12),
-            ("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red", 1560),
-            ("Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red", 630),
-            ("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green", 36),];
-        for (game_string, pow) in sample_data {
-            let game = parse_game(game_string);
-            assert_eq!(power(game), pow);
+    if bags.is_empty() {
+        let sum = part_one(&input);
+        println!("Part One - Sum: {sum}");
+    } else {
+        let games: Vec<_> = input.lines().map(parse_game).collect();
+        for bag in bags {
+            let ids = possible_games(&bag, &games, mode);
+            let sum: usize = ids.iter().sum();
+            let ids: Vec<String> = ids.iter().map(usize::to_string).collect();
+            let default = if bag == default_bag() { " (puzzle bag)" } else { "" };
+            println!("Bag {}{default} - Sum: {sum} - Possible games: {}", format_bag(&bag), ids.join(", "));
         }
     }
+    let pow_sum = part_two(&input);
+    println!("Part Two - Sum: {pow_sum}")
 }
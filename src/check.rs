@@ -0,0 +1,338 @@
+//! Validation of puzzle input files against the format each day's solver expects, used by
+//! `aoc check <day> <file>`.
+
+use std::fmt;
+
+use regex::Regex;
+
+use crate::day1::{Decoder, Mode};
+
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn problem(line: usize, message: impl Into<String>) -> Problem {
+    Problem {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Returns the input with CRLF line endings replaced, whitespace around lines removed and exactly
+/// one newline at the end.  Blank lines are dropped, except where the day's format separates
+/// parts with one: runs of them become a single blank line between the sections of day 5 and
+/// after the steps of day 8.
+pub fn normalise(day: usize, input: &str) -> String {
+    let mut lines: Vec<&str> = vec![];
+    for line in input.lines().map(str::trim) {
+        let separator = match day {
+            5 => true,
+            8 => lines.len() == 1,
+            _ => false,
+        };
+        if !line.is_empty() || (separator && lines.last().is_some_and(|last| !last.is_empty())) {
+            lines.push(line);
+        }
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        String::new()
+    } else {
+        lines.join("\n") + "\n"
+    }
+}
+
+fn check_whitespace(input: &str) -> Vec<Problem> {
+    let mut problems = vec![];
+    if input.is_empty() {
+        return vec![problem(1, "file is empty")];
+    }
+    let lines: Vec<&str> = input.split_terminator('\n').collect();
+    let first = lines.iter().position(|line| !line.trim().is_empty());
+    let last = lines.iter().rposition(|line| !line.trim().is_empty());
+    for (index, line) in lines.iter().enumerate() {
+        let number = index + 1;
+        let text = line.strip_suffix('\r').unwrap_or(line);
+        if text.len() < line.len() {
+            problems.push(problem(number, "CRLF line ending"));
+        }
+        if text.trim().is_empty() {
+            if Some(index) < first || Some(index) > last {
+                problems.push(problem(number, "blank line at the edge of the file"));
+            } else if !text.is_empty() {
+                problems.push(problem(number, "blank line contains whitespace"));
+            }
+            continue;
+        }
+        if text.trim_start().len() < text.len() {
+            problems.push(problem(number, "leading whitespace"));
+        }
+        if text.trim_end().len() < text.len() {
+            problems.push(problem(number, "trailing whitespace"));
+        }
+    }
+    if !input.ends_with('\n') {
+        problems.push(problem(lines.len(), "missing newline at end of file"));
+    }
+    problems
+}
+
+type Lines<'a> = [(usize, &'a str)];
+
+fn expect_lines(lines: &Lines, pattern: &str, description: &str) -> Vec<Problem> {
+    let regex = Regex::new(pattern).unwrap();
+    lines
+        .iter()
+        .filter(|(_, line)| !regex.is_match(line))
+        .map(|(number, line)| {
+            if line.is_empty() {
+                problem(*number, "unexpected blank line")
+            } else {
+                problem(*number, format!("expected {description}"))
+            }
+        })
+        .collect()
+}
+
+fn expect_grid(lines: &Lines, allowed: fn(char) -> bool) -> Vec<Problem> {
+    let width = lines[0].1.chars().count();
+    let mut problems = vec![];
+    for (number, line) in lines {
+        if line.chars().count() != width {
+            problems.push(problem(*number, format!("row has {} columns, expected {}", line.chars().count(), width)));
+        }
+        for (col, c) in line.chars().enumerate() {
+            if !allowed(c) {
+                problems.push(problem(*number, format!("unexpected character {:?} in column {}", c, col + 1)));
+            }
+        }
+    }
+    problems
+}
+
+fn expect_single_blank_lines(lines: &Lines) -> Vec<Problem> {
+    lines
+        .windows(2)
+        .filter(|pair| pair[0].1.is_empty() && pair[1].1.is_empty())
+        .map(|pair| problem(pair[1].0, "unexpected blank line"))
+        .collect()
+}
+
+/// Lines may contain any characters, but need a digit of any script or an English digit name
+/// in any case.  Names from other vocabularies are not known here.
+fn check_day_1(lines: &Lines) -> Vec<Problem> {
+    let mut problems = expect_lines(lines, r"^.+$", "a line");
+    let decoder = Decoder::new(Mode::Spelled).ignoring_case();
+    for (number, line) in lines {
+        if !line.is_empty() && decoder.calibration_value(line).is_none() {
+            problems.push(problem(*number, "no digit in line"));
+        }
+    }
+    problems
+}
+
+fn check_day_5(lines: &Lines) -> Vec<Problem> {
+    let mut problems = expect_single_blank_lines(lines);
+    let sections = lines.split(|(_, line)| line.is_empty()).filter(|section| !section.is_empty());
+    for (index, section) in sections.enumerate() {
+        let (header, rest) = (&section[..1], &section[1..]);
+        if index == 0 {
+            problems.extend(expect_lines(header, r"^seeds:( \d+)+$", "\"seeds:\" and numbers"));
+            if header[0].1.split_whitespace().skip(1).count() % 2 != 0 {
+                problems.push(problem(header[0].0, "seeds must come in start and length pairs"));
+            }
+            problems.extend(expect_lines(rest, r"^$", "a blank line after the seeds"));
+        } else {
+            problems.extend(expect_lines(header, r"^[a-z]+-to-[a-z]+ map:$", "a \"<from>-to-<to> map:\" header"));
+            problems.extend(expect_lines(rest, r"^\d+ \d+ \d+$", "three numbers"));
+            if rest.is_empty() {
+                problems.push(problem(header[0].0, "map has no ranges"));
+            }
+        }
+    }
+    problems
+}
+
+fn check_day_6(lines: &Lines) -> Vec<Problem> {
+    let mut problems = vec![];
+    let patterns = [(r"^Time:( +\d+)+$", "\"Time:\" and numbers"), (r"^Distance:( +\d+)+$", "\"Distance:\" and numbers")];
+    for (index, (pattern, description)) in patterns.iter().enumerate() {
+        match lines.get(index) {
+            Some(line) => problems.extend(expect_lines(&[*line], pattern, description)),
+            None => problems.push(problem(index + 1, format!("missing line with {description}"))),
+        }
+    }
+    if let [(_, times), (number, distances)] = lines[..] {
+        if times.split_whitespace().count() != distances.split_whitespace().count() {
+            problems.push(problem(number, "number of distances differs from number of times"));
+        }
+    }
+    problems.extend(lines.iter().skip(2).map(|(number, _)| problem(*number, "unexpected line after distances")));
+    problems
+}
+
+fn check_day_8(lines: &Lines) -> Vec<Problem> {
+    let mut problems = expect_lines(&lines[..1], r"^[LR]+$", "a sequence of L and R steps");
+    match lines.get(1) {
+        Some(line) => problems.extend(expect_lines(&[*line], r"^$", "a blank line after the steps")),
+        None => problems.push(problem(2, "missing blank line after the steps")),
+    }
+    let nodes = lines.get(2..).unwrap_or_default();
+    if nodes.is_empty() {
+        problems.push(problem(3, "no nodes"));
+    }
+    problems.extend(expect_lines(nodes, r"^\w+ = \(\w+, \w+\)$", "\"<node> = (<left>, <right>)\""));
+    problems
+}
+
+fn check_day_10(lines: &Lines) -> Vec<Problem> {
+    let mut problems = expect_grid(lines, |c| "|-LJ7F.S".contains(c));
+    let animals: Vec<usize> = lines
+        .iter()
+        .filter(|(_, line)| line.contains('S'))
+        .map(|(number, _)| *number)
+        .collect();
+    match animals[..] {
+        [_] => {}
+        [] => problems.push(problem(lines[0].0, "no starting position S")),
+        _ => problems.extend(animals[1..].iter().map(|number| problem(*number, "more than one starting position S"))),
+    }
+    problems
+}
+
+fn check_grammar(day: usize, lines: &Lines) -> Option<Vec<Problem>> {
+    let problems = match day {
+        1 => check_day_1(lines),
+        2 => expect_lines(
+            lines,
+            r"^Game \d+: \d+ [A-Za-z]+((, |; )\d+ [A-Za-z]+)*$",
+            "\"Game <id>: \" and draws like \"3 blue, 4 red; 1 green\"",
+        ),
+        3 => expect_grid(lines, |c| c.is_ascii_digit() || c == '.' || c.is_ascii_punctuation()),
+        4 => expect_lines(lines, r"^Card +\d+:( +\d+)+ \|( +\d+)+$", "\"Card <id>: <numbers> | <numbers>\""),
+        5 => check_day_5(lines),
+        6 => check_day_6(lines),
+        7 => expect_lines(lines, r"^[AKQJT2-9]{5} \d+$", "a hand of five cards and a bid"),
+        8 => check_day_8(lines),
+        9 => expect_lines(lines, r"^-?\d+( -?\d+)*$", "numbers separated by single spaces"),
+        10 => check_day_10(lines),
+        11 => expect_grid(lines, |c| c == '.' || c == '#'),
+        12 => expect_lines(lines, r"^[.#?]+ \d+(,\d+)*$", "springs followed by group sizes"),
+        _ => return None,
+    };
+    Some(problems)
+}
+
+/// Checks the input for a day, returning every whitespace and format problem ordered by line,
+/// or `None` if there is no solver for that day.
+pub fn check(day: usize, input: &str) -> Option<Vec<Problem>> {
+    let numbered: Vec<(usize, &str)> = input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .collect();
+    let first = numbered.iter().position(|(_, line)| !line.is_empty());
+    let last = numbered.iter().rposition(|(_, line)| !line.is_empty());
+    if !(1..=12).contains(&day) {
+        return None;
+    }
+    let mut problems = check_whitespace(input);
+    if let (Some(first), Some(last)) = (first, last) {
+        problems.extend(check_grammar(day, &numbered[first..=last])?);
+    }
+    problems.sort_by_key(|problem| problem.line);
+    Some(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(day: usize, input: &str) -> Vec<String> {
+        check(day, input).unwrap().iter().map(|problem| problem.to_string()).collect()
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let input = "\r\nCard 1: 41 48 | 83 86\r\n Card 2: 13 32 | 61 30  \n\nCard 3: 1 21 | 69 82";
+        assert_eq!(
+            messages(4, input),
+            [
+                "line 1: CRLF line ending",
+                "line 1: blank line at the edge of the file",
+                "line 2: CRLF line ending",
+                "line 3: leading whitespace",
+                "line 3: trailing whitespace",
+                "line 4: unexpected blank line",
+                "line 5: missing newline at end of file",
+            ]
+        );
+        assert_eq!(normalise(4, input), "Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30\nCard 3: 1 21 | 69 82\n");
+        assert_eq!(normalise(4, " \n\n"), "");
+        assert_eq!(normalise(5, "seeds: 1 2\r\n\r\n \r\na-to-b map:\r\n1 2 3\r\n\r\n"), "seeds: 1 2\n\na-to-b map:\n1 2 3\n");
+        assert_eq!(normalise(8, "LR\n\n\nAAA = (AAA, AAA)\n\nBBB = (AAA, AAA)\n"), "LR\n\nAAA = (AAA, AAA)\nBBB = (AAA, AAA)\n");
+        assert_eq!(messages(9, ""), ["line 1: file is empty"]);
+    }
+
+    #[test]
+    fn test_grammar() {
+        assert_eq!(messages(2, "Game 1: 3 blue, 4 red; 1 red\nGame 2: three purple\n"), ["line 2: expected \"Game <id>: \" and draws like \"3 blue, 4 red; 1 green\""]);
+        assert_eq!(messages(3, "467..\n...*\n..3a.\n"), ["line 2: row has 4 columns, expected 5", "line 3: unexpected character 'a' in column 4"]);
+        assert_eq!(messages(6, "Time: 7 15\nDistance: 9\n"), ["line 2: number of distances differs from number of times"]);
+        assert_eq!(messages(8, "LR\nAAA = (BBB, CCC)\n"), ["line 2: expected a blank line after the steps", "line 3: no nodes"]);
+        assert_eq!(messages(10, "S-7\n|.|\nL-S\n"), ["line 3: more than one starting position S"]);
+        assert_eq!(messages(1, "two1nine\nabcdef\n"), ["line 2: no digit in line"]);
+        assert_eq!(messages(1, "x٣y\nTWO-ONE!\n"), Vec::<String>::new());
+        assert!(check(13, "").is_none());
+    }
+
+    #[test]
+    fn test_day_5() {
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n52 50\n\n\nsoil-to-fertilizer:\n0 15 37\n";
+        assert_eq!(
+            messages(5, input),
+            [
+                "line 1: seeds must come in start and length pairs",
+                "line 5: expected three numbers",
+                "line 7: unexpected blank line",
+                "line 8: expected a \"<from>-to-<to> map:\" header",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalise_fixes_whitespace() {
+        for day in 1..=12 {
+            let input = std::fs::read_to_string(format!("data/day{day}.dat")).unwrap();
+            let mut messy = String::from(" \r\n\r\n");
+            for (index, line) in input.lines().enumerate() {
+                messy += &format!("  {line} \r\n");
+                if line.is_empty() || (index % 3 == 2 && day != 5 && day != 8) {
+                    messy += "\t\r\n";
+                }
+            }
+            assert_eq!(check(day, &normalise(day, &messy)).unwrap(), [], "day {day}");
+        }
+    }
+
+    #[test]
+    fn test_data_files() {
+        let input = std::fs::read_to_string("data/day1.dat").unwrap();
+        assert_eq!(messages(1, &input), ["line 1000: missing newline at end of file"]);
+        for day in 2..=12 {
+            let input = std::fs::read_to_string(format!("data/day{day}.dat")).unwrap();
+            assert_eq!(check(day, &input).unwrap(), [], "day {day}");
+        }
+    }
+}
@@ -0,0 +1,995 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::{self, ErrorKind, Read, Write};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::sync_channel;
+use std::sync::Mutex;
+use std::thread;
+use memoize::memoize;
+
+use crate::token_scanner::{TokenMatch, TokenScanner};
+
+const DIGIT_NAMES: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+const BUILTIN_VOCABULARIES: [(&str, &str, [&str; 10]); 4] = [
+    ("english", "en", DIGIT_NAMES),
+    ("german", "de", ["null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"]),
+    ("french", "fr", ["zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"]),
+    ("spanish", "es", ["cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve"]),
+];
+
+/// What counts as a digit in a calibration line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Only the characters 0 to 9, as in part 1.
+    Digits,
+    /// Also the digit names from "zero" to "nine", as in part 2.
+    Spelled,
+    /// Numbers written in English words, like "forty-two" or "three hundred and seven", and
+    /// single digits.
+    Phrases,
+}
+
+/// Spelled-out digit names, in addition to the digits 0 to 9.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Vocabulary {
+    names: Vec<(String, usize)>,
+}
+
+impl Vocabulary {
+    pub fn english() -> Vocabulary {
+        Vocabulary::builtin("english").unwrap()
+    }
+
+    /// Returns one of the built-in vocabularies by language name or code, e.g. "german" or "de".
+    pub fn builtin(language: &str) -> Option<Vocabulary> {
+        let (_, _, names) = BUILTIN_VOCABULARIES
+            .iter()
+            .find(|(name, code, _)| *name == language || *code == language)?;
+        Some(Vocabulary {
+            names: names.iter().enumerate().map(|(value, name)| (name.to_string(), value)).collect(),
+        })
+    }
+
+    /// Parses lines of the form `token = digit`.  Empty lines and lines starting with `#` are
+    /// ignored.  Several tokens can have the same value, and tokens may overlap or be prefixes of
+    /// each other.
+    pub fn parse(text: &str) -> Result<Vocabulary, String> {
+        let mut names: Vec<(String, usize)> = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| Err(format!("line {}: {message}", index + 1));
+            let Some((token, value)) = line.rsplit_once('=') else {
+                return error("expected \"token = digit\"");
+            };
+            let token = token.trim();
+            let value = match value.trim().parse::<usize>() {
+                Ok(value) if value <= 9 => value,
+                _ => return error(&format!("{:?} is not a digit", value.trim())),
+            };
+            if token.is_empty() {
+                return error("empty token");
+            }
+            if names.iter().any(|(name, other)| name == token && *other != value) {
+                return error(&format!("conflicting values for {token:?}"));
+            }
+            names.push((token.to_string(), value));
+        }
+        Ok(Vocabulary { names })
+    }
+
+    pub fn load(filename: &str) -> Result<Vocabulary, String> {
+        let text = read_to_string(filename).map_err(|error| format!("{filename}: {error}"))?;
+        Vocabulary::parse(&text).map_err(|error| format!("{filename}: {error}"))
+    }
+}
+
+#[memoize]
+fn digit_encoding(vocabulary: Vocabulary) -> HashMap<String, usize> {
+    let mut map: HashMap<String, usize> = HashMap::new();
+
+    for (name, value) in vocabulary.names {
+        map.insert(name, value);
+    }
+    for digit in 0..10 {
+        map.insert(digit.to_string(), digit);
+    }
+
+    map
+}
+
+/// The zeros of all runs of decimal digits (general category Nd) in Unicode 16.0.  Each is
+/// followed by the digits one to nine.
+const UNICODE_ZEROS: [u32; 76] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x10D40, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450,
+    0x114D0, 0x11650, 0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50,
+    0x11D50, 0x11DA0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50, 0x16D70, 0x1CCF0, 0x1D7CE,
+    0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950, 0x1FBF0,
+];
+
+const TEEN_NAMES: [&str; 10] = [
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS_NAMES: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const SCALE_NAMES: [(&str, usize); 2] = [("thousand", 1_000), ("million", 1_000_000)];
+
+/// The tokens that make up numbers.  Apart from the digits, only used in number phrases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Word {
+    /// A digit character, which is a number on its own.
+    Digit(usize),
+    /// A digit name, "zero" to "nine".
+    Unit(usize),
+    Teen(usize),
+    Tens(usize),
+    Hundred,
+    Scale(usize),
+    And,
+}
+
+/// A partially read number phrase.
+#[derive(Debug, Clone, Copy)]
+struct Phrase {
+    /// Sum of the completed thousands and millions.
+    total: usize,
+    /// The number below the last scale word read.
+    group: usize,
+    last: Word,
+    /// The next scale word must be smaller than this.
+    scale_limit: usize,
+}
+
+impl Phrase {
+    fn start(word: Word) -> Option<Phrase> {
+        let group = match word {
+            Word::Digit(value) | Word::Unit(value) | Word::Teen(value) | Word::Tens(value) => value,
+            Word::Hundred | Word::Scale(_) | Word::And => return None,
+        };
+        Some(Phrase {
+            total: 0,
+            group,
+            last: word,
+            scale_limit: usize::MAX,
+        })
+    }
+
+    /// Returns the phrase extended by `word`, if that is still a valid number.
+    fn extend(self, word: Word) -> Option<Phrase> {
+        let after_scale = matches!(self.last, Word::Hundred | Word::Scale(_) | Word::And);
+        let mut next = Phrase { last: word, ..self };
+        match (self.last, word) {
+            (Word::Digit(_), _) => return None,
+            (Word::Tens(_), Word::Unit(value)) if value > 0 => next.group += value,
+            (_, Word::Unit(value) | Word::Teen(value) | Word::Tens(value)) if after_scale && value > 0 => {
+                next.group += value
+            }
+            (Word::Unit(_), Word::Hundred) if (1..10).contains(&self.group) => next.group *= 100,
+            (Word::Unit(_) | Word::Teen(_) | Word::Tens(_) | Word::Hundred, Word::Scale(scale))
+                if scale < self.scale_limit && self.group > 0 =>
+            {
+                next.total += self.group * scale;
+                next.group = 0;
+                next.scale_limit = scale;
+            }
+            (Word::Hundred | Word::Scale(_), Word::And) => {}
+            _ => return None,
+        }
+        Some(next)
+    }
+
+    fn value(&self) -> Option<usize> {
+        match self.last {
+            Word::And => None,
+            _ => Some(self.total + self.group),
+        }
+    }
+}
+
+/// Finds the digits in calibration lines.
+pub struct Decoder {
+    /// The tokens of `scanner`, kept to build a case-insensitive one.
+    words: Vec<(String, Word)>,
+    scanner: TokenScanner<Word>,
+    phrases: bool,
+    ignore_case: bool,
+}
+
+impl Decoder {
+    pub fn new(mode: Mode) -> Decoder {
+        match mode {
+            Mode::Digits => Decoder::with_vocabulary(&Vocabulary { names: vec![] }),
+            Mode::Spelled => Decoder::with_vocabulary(&Vocabulary::english()),
+            Mode::Phrases => Decoder::phrases(),
+        }
+    }
+
+    pub fn with_vocabulary(vocabulary: &Vocabulary) -> Decoder {
+        Decoder::from_words(Decoder::digit_words(vocabulary), false)
+    }
+
+    fn phrases() -> Decoder {
+        let mut words = Decoder::digit_words(&Vocabulary::english());
+        words.extend(TEEN_NAMES.iter().zip(10..).map(|(name, value)| (name.to_string(), Word::Teen(value))));
+        words.extend(TENS_NAMES.iter().zip((20..).step_by(10)).map(|(name, value)| (name.to_string(), Word::Tens(value))));
+        words.extend(SCALE_NAMES.iter().map(|(name, value)| (name.to_string(), Word::Scale(*value))));
+        words.extend([("hundred".to_string(), Word::Hundred), ("and".to_string(), Word::And)]);
+        Decoder::from_words(words, true)
+    }
+
+    fn from_words(words: Vec<(String, Word)>, phrases: bool) -> Decoder {
+        Decoder {
+            scanner: TokenScanner::new(words.iter().map(|(token, word)| (token.as_str(), *word))),
+            words,
+            phrases,
+            ignore_case: false,
+        }
+    }
+
+    /// The digits and the names from `digit_encoding`'s token map.
+    fn digit_words(vocabulary: &Vocabulary) -> Vec<(String, Word)> {
+        digit_encoding(vocabulary.clone())
+            .into_iter()
+            .map(|(token, value)| {
+                let word = if token == value.to_string() { Word::Digit(value) } else { Word::Unit(value) };
+                (token, word)
+            })
+            .collect()
+    }
+
+    /// Makes the decoder match names regardless of case, in the names as well as in the lines.
+    /// Panics if two names that differ only in case have different values.
+    pub fn ignoring_case(self) -> Decoder {
+        let mut words: Vec<(String, Word)> = vec![];
+        for (token, word) in self.words {
+            let token = normalise(&token, true);
+            match words.iter().find(|(other, _)| *other == token) {
+                Some((_, other)) => assert!(*other == word, "names matching {token:?} have different values"),
+                None => words.push((token, word)),
+            }
+        }
+        Decoder { ignore_case: true, ..Decoder::from_words(words, self.phrases) }
+    }
+
+    /// Finds the first and the last number in the line, with their byte spans in the line.
+    pub(crate) fn first_and_last(&self, line: &str) -> Option<(TokenMatch<usize>, TokenMatch<usize>)> {
+        if line.is_ascii() && !self.ignore_case {
+            return self.first_and_last_normalised(line);
+        }
+        let normalised = normalise(line, self.ignore_case);
+        let (first, last) = self.first_and_last_normalised(&normalised)?;
+        let original_span = |found: TokenMatch<usize>| TokenMatch {
+            start: original_offset(line, &normalised, found.start),
+            end: original_offset(line, &normalised, found.end),
+            value: found.value,
+        };
+        Some((original_span(first), original_span(last)))
+    }
+
+    /// Of several numbers starting at the same position, the longest one counts.
+    fn first_and_last_normalised(&self, line: &str) -> Option<(TokenMatch<usize>, TokenMatch<usize>)> {
+        if self.phrases {
+            let phrases = self.phrases_in(line);
+            return Some((*phrases.first()?, *phrases.last()?));
+        }
+        let mut matches = self.scanner.find_iter(line).map(|found| TokenMatch {
+            start: found.start,
+            end: found.end,
+            value: match found.value {
+                Word::Digit(value) | Word::Unit(value) => value,
+                _ => unreachable!("only digits outside of phrase mode"),
+            },
+        });
+        let first = matches.next()?;
+        let (mut first, mut last) = (first, first);
+        for found in matches {
+            if found.start < first.start || (found.start == first.start && found.end > first.end) {
+                first = found;
+            }
+            if found.start > last.start || (found.start == last.start && found.end > last.end) {
+                last = found;
+            }
+        }
+        Some((first, last))
+    }
+
+    /// Returns the longest number phrase starting at each position, ordered by position, except
+    /// for those inside of an earlier phrase, like "one" in "twentyone".  Phrases do not share
+    /// words: the next phrase starts inside or behind the last word of the previous one, so the
+    /// overlapping "one" in "twone" is a number of its own.
+    fn phrases_in(&self, line: &str) -> Vec<TokenMatch<usize>> {
+        let mut words: Vec<TokenMatch<Word>> = self.scanner.find_iter(line).collect();
+        words.sort_by_key(|word| (word.start, word.end));
+
+        /// The longest phrase found so far as (end, value, start of its last word).
+        type Longest = Option<(usize, usize, usize)>;
+
+        // Searches all ways of continuing the phrase and returns the longest valid one.
+        fn longest(line: &str, words: &[TokenMatch<Word>], phrase: Phrase, last: &TokenMatch<Word>) -> Longest {
+            let mut best = phrase.value().map(|value| (last.end, value, last.start));
+            let separator = matches!(line.as_bytes().get(last.end), Some(b' ' | b'-')) as usize;
+            for next_start in last.end..=last.end + separator {
+                let first = words.partition_point(|word| word.start < next_start);
+                for word in words[first..].iter().take_while(|word| word.start == next_start) {
+                    if let Some(longer) = phrase.extend(word.value).and_then(|next| longest(line, words, next, word)) {
+                        if best.is_none_or(|(best_end, _, _)| longer.0 > best_end) {
+                            best = Some(longer);
+                        }
+                    }
+                }
+            }
+            best
+        }
+
+        let mut phrases: Vec<TokenMatch<usize>> = vec![];
+        let mut last_word_start = None;
+        for (index, word) in words.iter().enumerate() {
+            if index > 0 && words[index - 1].start == word.start {
+                continue;
+            }
+            if last_word_start.is_some_and(|last_word_start| word.start <= last_word_start) {
+                continue;
+            }
+            let starting_here = words[index..].iter().take_while(|other| other.start == word.start);
+            let Some((end, value, last_start)) = starting_here
+                .filter_map(|word| longest(line, &words, Phrase::start(word.value)?, word))
+                .max_by_key(|(end, _, _)| *end)
+            else {
+                continue;
+            };
+            if phrases.last().is_none_or(|previous| end > previous.end) {
+                phrases.push(TokenMatch { start: word.start, end, value });
+                last_word_start = Some(last_start);
+            }
+        }
+        phrases
+    }
+
+    /// Combines the first and the last number in the line by writing them after each other, so
+    /// that "4" and "2" give 42 and "forty-two" and "seven" give 427.  Returns `None` if the
+    /// line contains no number or the value does not fit in a `usize`.
+    pub fn calibration_value(&self, line: &str) -> Option<usize> {
+        self.checked_calibration_value(line).ok()
+    }
+
+    /// Like `calibration_value`, but says why there is no value.
+    fn checked_calibration_value(&self, line: &str) -> Result<usize, &'static str> {
+        let (first, last) = self.first_and_last(line).ok_or("no digit in line")?;
+        concatenate(first.value, last.value).ok_or("calibration value overflows")
+    }
+
+    pub fn sum(&self, input: &str) -> usize {
+        input
+            .lines()
+            .map(|line| extract_calibration_value(self, line))
+            .try_fold(0usize, usize::checked_add)
+            .expect("sum of calibration values overflows")
+    }
+
+    /// Decodes every line of the input, keeping the lines without a number instead of failing.
+    pub fn explain<'a>(&self, input: &'a str) -> Vec<Explanation<'a>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let found = |found: TokenMatch<usize>| Found {
+                    chars: line[..found.start].chars().count()..line[..found.end].chars().count(),
+                    token: &line[found.start..found.end],
+                    value: found.value,
+                };
+                Explanation {
+                    number: index + 1,
+                    text: line,
+                    first_and_last: self.first_and_last(line).map(|(first, last)| (found(first), found(last))),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A number found in a calibration line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<'a> {
+    /// Character positions in the line, counting from 0.
+    pub chars: Range<usize>,
+    pub token: &'a str,
+    pub value: usize,
+}
+
+/// How a calibration line was decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    /// The first and the last number, or `None` if the line has none and was rejected.
+    pub first_and_last: Option<(Found<'a>, Found<'a>)>,
+}
+
+impl Explanation<'_> {
+    pub fn value(&self) -> Option<usize> {
+        let (first, last) = self.first_and_last.as_ref()?;
+        concatenate(first.value, last.value)
+    }
+}
+
+/// Writes the explanations as a table, followed by the sum of the values.
+pub fn write_table(explanations: &[Explanation], output: &mut dyn Write) -> io::Result<()> {
+    let found = |found: &Found| format!("{}..{} {:?}", found.chars.start, found.chars.end, found.token);
+    let rows: Vec<[String; 4]> = explanations
+        .iter()
+        .map(|explanation| match (&explanation.first_and_last, explanation.value()) {
+            (Some((first, last)), Some(value)) => [explanation.number.to_string(), found(first), found(last), value.to_string()],
+            (Some((first, last)), None) => [explanation.number.to_string(), found(first), found(last), "overflows".to_string()],
+            _ => [explanation.number.to_string(), "-".to_string(), "-".to_string(), "no digits".to_string()],
+        })
+        .collect();
+    let header = ["line", "first", "last", "value"].map(String::from);
+    let widths: Vec<usize> = (0..4)
+        .map(|column| rows.iter().chain([&header]).map(|row| row[column].chars().count()).max().unwrap())
+        .collect();
+    for (row, text) in [(&header, "text")].into_iter().chain(rows.iter().zip(explanations.iter().map(|explanation| explanation.text))) {
+        writeln!(
+            output,
+            "{:>w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {text}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        )?;
+    }
+    let sum: usize = explanations.iter().filter_map(Explanation::value).sum();
+    let rejected = explanations.iter().filter(|explanation| explanation.value().is_none()).count();
+    writeln!(output, "sum: {sum}, {rejected} line(s) rejected")
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the explanations as CSV with a header line.  Character positions count from 0, the end
+/// position is exclusive.
+pub fn write_csv(explanations: &[Explanation], output: &mut dyn Write) -> io::Result<()> {
+    writeln!(output, "line,text,first_start,first_end,first_token,last_start,last_end,last_token,value,status")?;
+    for explanation in explanations {
+        let text = csv_field(explanation.text);
+        match (&explanation.first_and_last, explanation.value()) {
+            (Some((first, last)), Some(value)) => writeln!(
+                output,
+                "{},{text},{},{},{},{},{},{},{value},ok",
+                explanation.number,
+                first.chars.start,
+                first.chars.end,
+                csv_field(first.token),
+                last.chars.start,
+                last.chars.end,
+                csv_field(last.token)
+            )?,
+            (Some((first, last)), None) => writeln!(
+                output,
+                "{},{text},{},{},{},{},{},{},,overflows",
+                explanation.number,
+                first.chars.start,
+                first.chars.end,
+                csv_field(first.token),
+                last.chars.start,
+                last.chars.end,
+                csv_field(last.token)
+            )?,
+            _ => writeln!(output, "{},{text},,,,,,,,no digits", explanation.number)?,
+        }
+    }
+    Ok(())
+}
+
+fn unicode_digit(c: char) -> Option<u32> {
+    let code = c as u32;
+    let zero = UNICODE_ZEROS[UNICODE_ZEROS.partition_point(|&zero| zero <= code).checked_sub(1)?];
+    (code - zero < 10).then_some(code - zero)
+}
+
+/// Replaces the decimal digits of all scripts by ASCII digits and, if `ignore_case` is set, upper
+/// case letters by lower case ones.  Each character is replaced by exactly one character, so
+/// that character positions are the same in the result.
+pub fn normalise(line: &str, ignore_case: bool) -> String {
+    line.chars()
+        .map(|c| {
+            if let Some(digit) = unicode_digit(c) {
+                return char::from_digit(digit, 10).unwrap();
+            }
+            let mut lower = c.to_lowercase();
+            match (ignore_case, lower.next(), lower.next()) {
+                (true, Some(lower), None) => lower,
+                _ => c,
+            }
+        })
+        .collect()
+}
+
+/// Translates a byte offset in the normalised line to one in the original line.
+fn original_offset(line: &str, normalised: &str, offset: usize) -> usize {
+    let chars = normalised[..offset].chars().count();
+    line.char_indices().nth(chars).map_or(line.len(), |(index, _)| index)
+}
+
+/// Writes the numbers after each other, or returns `None` if the result does not fit.
+fn concatenate(first: usize, last: usize) -> Option<usize> {
+    let mut shift: usize = 10;
+    while shift <= last {
+        shift = shift.checked_mul(10)?;
+    }
+    first.checked_mul(shift)?.checked_add(last)
+}
+
+pub(crate) fn extract_calibration_value(decoder: &Decoder, line: &str) -> usize {
+    decoder
+        .checked_calibration_value(line)
+        .unwrap_or_else(|error| panic!("{error}: \"{line}\""))
+}
+
+pub fn sum_calibration_values(input: &str, mode: Mode) -> usize {
+    Decoder::new(mode).sum(input)
+}
+
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+#[derive(Debug, PartialEq)]
+pub struct StreamError {
+    /// The input line the error occurred in, if any.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn stream_error(line: Option<usize>, message: impl Into<String>) -> StreamError {
+    StreamError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Fills `buffer` from `input`, returning less than its length only at the end of the input.
+fn read_fully(input: &mut impl Read, buffer: &mut [u8]) -> Result<usize, StreamError> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match input.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(count) => filled += count,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(stream_error(None, error.to_string())),
+        }
+    }
+    Ok(filled)
+}
+
+/// Sums the calibration values of one chunk of whole lines, the first of which is line `line`.
+fn sum_chunk(decoder: &Decoder, line: usize, chunk: &[u8]) -> Result<usize, StreamError> {
+    let text = std::str::from_utf8(chunk).map_err(|error| {
+        let line = line + chunk[..error.valid_up_to()].iter().filter(|&&byte| byte == b'\n').count();
+        stream_error(Some(line), "invalid UTF-8")
+    })?;
+    let mut sum: usize = 0;
+    for (index, text) in text.lines().enumerate() {
+        let value = decoder
+            .checked_calibration_value(text)
+            .map_err(|error| stream_error(Some(line + index), error))?;
+        sum = sum
+            .checked_add(value)
+            .ok_or_else(|| stream_error(Some(line + index), "sum overflows"))?;
+    }
+    Ok(sum)
+}
+
+/// Sums the calibration values of a possibly very large input on `workers` threads.  The input
+/// is read in chunks of about `chunk_size` bytes, split at line boundaries, of which at most
+/// two per worker are held in memory at any time, plus the longest line if that is longer.
+/// Errors are reported for the first line that has one, as far as the input has been read.
+pub fn sum_stream(mut input: impl Read, decoder: &Decoder, workers: usize, chunk_size: usize) -> Result<usize, StreamError> {
+    assert!(workers > 0 && chunk_size > 0);
+    let (sender, receiver) = sync_channel::<(usize, Vec<u8>)>(workers);
+    let receiver = Mutex::new(receiver);
+    let failed = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut result = Ok(0usize);
+                    loop {
+                        // Release the lock before working on the chunk.
+                        let received = receiver.lock().unwrap().recv();
+                        let Ok((line, chunk)) = received else {
+                            break;
+                        };
+                        // After an error, keep receiving so that the reader does not block.
+                        if result.is_err() {
+                            continue;
+                        }
+                        result = sum_chunk(decoder, line, &chunk).and_then(|sum| {
+                            result.as_ref().unwrap().checked_add(sum).ok_or_else(|| stream_error(None, "sum overflows"))
+                        });
+                        if result.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    result
+                })
+            })
+            .collect();
+
+        let mut read_result = Ok(());
+        let mut pending: Vec<u8> = vec![];
+        let mut line = 1;
+        while !failed.load(Ordering::Relaxed) {
+            let mut chunk = std::mem::take(&mut pending);
+            let start = chunk.len();
+            chunk.resize(start + chunk_size, 0);
+            let count = match read_fully(&mut input, &mut chunk[start..]) {
+                Ok(count) => count,
+                Err(error) => {
+                    read_result = Err(error);
+                    break;
+                }
+            };
+            chunk.truncate(start + count);
+            let at_end = count < chunk_size;
+            // Lines longer than a chunk are collected over several reads.
+            let end = match chunk.iter().rposition(|&byte| byte == b'\n') {
+                _ if at_end => chunk.len(),
+                Some(newline) => newline + 1,
+                None => {
+                    pending = chunk;
+                    continue;
+                }
+            };
+            pending = chunk.split_off(end);
+            if !chunk.is_empty() {
+                let lines = chunk.iter().filter(|&&byte| byte == b'\n').count() + (chunk.last() != Some(&b'\n')) as usize;
+                sender.send((line, chunk)).unwrap();
+                line += lines;
+            }
+            if at_end {
+                break;
+            }
+        }
+        drop(sender);
+
+        let results: Vec<Result<usize, StreamError>> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        read_result?;
+        let mut sum: usize = 0;
+        let mut errors = vec![];
+        for result in results {
+            match result {
+                Ok(partial) => match sum.checked_add(partial) {
+                    Some(total) => sum = total,
+                    None => errors.push(stream_error(None, "sum overflows")),
+                },
+                Err(error) => errors.push(error),
+            }
+        }
+        // Overflow errors, which have no line, come last.
+        match errors.into_iter().min_by_key(|error| error.line.unwrap_or(usize::MAX)) {
+            Some(error) => Err(error),
+            None => Ok(sum),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_INPUT_1: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    static TEST_INPUT_2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(sum_calibration_values(TEST_INPUT_1, Mode::Digits), 142);
+        let decoder = Decoder::new(Mode::Digits);
+        assert_eq!(decoder.calibration_value("two1nine"), Some(11));
+        assert_eq!(decoder.calibration_value("eightwothree"), None);
+    }
+
+    #[test]
+    fn test_spelled() {
+        assert_eq!(sum_calibration_values(TEST_INPUT_2, Mode::Spelled), 281);
+        assert_eq!(sum_calibration_values(TEST_INPUT_1, Mode::Spelled), 142);
+    }
+
+    #[test]
+    fn test_overlapping_names() {
+        let decoder = Decoder::new(Mode::Spelled);
+        assert_eq!(decoder.calibration_value("twone"), Some(21));
+        assert_eq!(decoder.calibration_value("eightwo"), Some(82));
+        assert_eq!(decoder.calibration_value("xxseveninexx"), Some(79));
+        assert_eq!(decoder.calibration_value("5"), Some(55));
+        assert_eq!(decoder.calibration_value("fiv tw"), None);
+    }
+
+    #[test]
+    fn test_builtin_vocabularies() {
+        let german = Decoder::with_vocabulary(&Vocabulary::builtin("german").unwrap());
+        assert_eq!(german.calibration_value("xxzweinsxx"), Some(21));
+        assert_eq!(german.calibration_value("fünfundzwanzig"), Some(55));
+        assert_eq!(german.calibration_value("siebenacht9"), Some(79));
+        let french = Decoder::with_vocabulary(&Vocabulary::builtin("fr").unwrap());
+        assert_eq!(french.calibration_value("zérodeuxtroisneuf"), Some(9));
+        assert_eq!(french.calibration_value("quatrehuit"), Some(48));
+        let spanish = Decoder::with_vocabulary(&Vocabulary::builtin("spanish").unwrap());
+        assert_eq!(spanish.calibration_value("cincuentaseisiete"), Some(67));
+        assert_eq!(spanish.calibration_value("one"), None);
+        assert_eq!(Vocabulary::builtin("klingon"), None);
+    }
+
+    #[test]
+    fn test_vocabulary_file() {
+        let vocabulary = Vocabulary::parse("# prefixes of each other\nab = 1\n\nabc = 2\n  bc=3\nuno = 1\n").unwrap();
+        let decoder = Decoder::with_vocabulary(&vocabulary);
+        // "abc" and "ab" start at the same position, the longer one wins.
+        assert_eq!(decoder.calibration_value("xabcx"), Some(23));
+        assert_eq!(decoder.calibration_value("xabx"), Some(11));
+        assert_eq!(decoder.calibration_value("bcab"), Some(31));
+        assert_eq!(decoder.calibration_value("7abc"), Some(73));
+
+        assert_eq!(Vocabulary::parse("one 1").unwrap_err(), "line 1: expected \"token = digit\"");
+        assert_eq!(Vocabulary::parse("\nten = 10").unwrap_err(), "line 2: \"10\" is not a digit");
+        assert_eq!(Vocabulary::parse(" = 1").unwrap_err(), "line 1: empty token");
+        assert_eq!(Vocabulary::parse("a = 1\na = 2").unwrap_err(), "line 2: conflicting values for \"a\"");
+    }
+
+    #[test]
+    fn test_phrases() {
+        let decoder = Decoder::new(Mode::Phrases);
+        let numbers = |line| decoder.phrases_in(line).iter().map(|phrase| phrase.value).collect::<Vec<usize>>();
+        assert_eq!(numbers("twelve"), vec![12]);
+        assert_eq!(numbers("xforty-twox"), vec![42]);
+        assert_eq!(numbers("three hundred and seven"), vec![307]);
+        assert_eq!(numbers("threehundredandseven"), vec![307]);
+        assert_eq!(numbers("two thousand and twenty-three"), vec![2023]);
+        assert_eq!(numbers("one million two hundred thousand nineteen"), vec![1_200_019]);
+        assert_eq!(numbers("fivehundredandx"), vec![500]);
+        assert_eq!(numbers("hundred and one"), vec![1]);
+        assert_eq!(numbers("zero hundred"), vec![0]);
+        assert_eq!(sum_calibration_values(TEST_INPUT_2, Mode::Phrases), 29 + 83 + 13 + 24 + 42 + 14 + 716);
+    }
+
+    #[test]
+    fn test_overflowing_calibration_value() {
+        assert_eq!(concatenate(42, 7), Some(427));
+        assert_eq!(concatenate(1, 10), Some(110));
+        assert_eq!(concatenate(usize::MAX, 5), None);
+        assert_eq!(concatenate(5, usize::MAX), None);
+        let decoder = Decoder::new(Mode::Phrases);
+        let line = "nine hundred ninety-nine million x nine hundred ninety-nine million";
+        assert_eq!(decoder.checked_calibration_value(line), Ok(999_000_000_999_000_000));
+    }
+
+    #[test]
+    fn test_overlapping_and_adjacent_phrases() {
+        let decoder = Decoder::new(Mode::Phrases);
+        let numbers = |line| decoder.phrases_in(line).iter().map(|phrase| phrase.value).collect::<Vec<usize>>();
+        // Words inside a longer phrase do not count on their own.
+        assert_eq!(numbers("twentyone"), vec![21]);
+        assert_eq!(numbers("seventeen"), vec![17]);
+        // Overlapping words are separate numbers.
+        assert_eq!(numbers("twone"), vec![2, 1]);
+        assert_eq!(numbers("sixtyoneight"), vec![61, 8]);
+        // Adjacent words that do not form a number are separate numbers.
+        assert_eq!(numbers("twelvethirteen"), vec![12, 13]);
+        assert_eq!(numbers("one two"), vec![1, 2]);
+        assert_eq!(numbers("forty fifty"), vec![40, 50]);
+        assert_eq!(numbers("ninety9"), vec![90, 9]);
+        assert_eq!(numbers("99"), vec![9, 9]);
+        // Words of a phrase are not reused by the next one.
+        assert_eq!(numbers("one thousand one thousand"), vec![1001]);
+        assert_eq!(numbers("one thousand one thousand two"), vec![1001, 2]);
+        assert_eq!(numbers("twenty one hundred"), vec![21]);
+        assert_eq!(numbers("twentyoneight"), vec![21, 8]);
+
+        assert_eq!(decoder.calibration_value("forty-two"), Some(4242));
+        assert_eq!(decoder.calibration_value("forty-twoxseven"), Some(427));
+        assert_eq!(decoder.calibration_value("5 and ten"), Some(510));
+        assert_eq!(decoder.calibration_value("zero"), Some(0));
+        assert_eq!(decoder.calibration_value("hundred"), None);
+    }
+
+    #[test]
+    fn test_unicode_digits() {
+        let decoder = Decoder::new(Mode::Digits);
+        assert_eq!(decoder.calibration_value("٣abc٧"), Some(37));
+        assert_eq!(decoder.calibration_value("x۴y"), Some(44));
+        assert_eq!(decoder.calibration_value("पाँच५नौ९"), Some(59));
+        assert_eq!(decoder.calibration_value("１two２"), Some(12));
+        assert_eq!(decoder.calibration_value("𝟖a𝟗"), Some(89));
+        assert_eq!(decoder.calibration_value("½Ⅻ²"), None);
+        assert_eq!(Decoder::new(Mode::Spelled).calibration_value("٣xninex"), Some(39));
+        assert_eq!(Decoder::new(Mode::Phrases).calibration_value("forty-two٧"), Some(427));
+
+        // Spans are byte offsets in the original line.
+        let (first, last) = decoder.first_and_last("é٣é7").unwrap();
+        assert_eq!((first.start, first.end, last.start, last.end), (2, 4, 6, 7));
+
+        for zero in UNICODE_ZEROS {
+            let digits: String = (zero..zero + 10).map(|code| char::from_u32(code).unwrap()).collect();
+            assert_eq!(normalise(&digits, false), "0123456789");
+            assert!(digits.chars().all(char::is_numeric));
+        }
+    }
+
+    #[test]
+    fn test_unicode_digits_match_ascii() {
+        use crate::property::check;
+        use crate::rng::Rng;
+
+        // Lines with ASCII digits and letters, and the same lines with the digits in other scripts.
+        check(
+            |rng: &mut Rng| {
+                let ascii: String = rng
+                    .vec(1..20, |rng| *rng.choose(&['1', '2', '7', '0', 'o', 'n', 'e', 'x', 'É']))
+                    .into_iter()
+                    .collect();
+                let scripts: Vec<usize> = ascii.chars().map(|_| rng.range(0..UNICODE_ZEROS.len())).collect();
+                (ascii, scripts)
+            },
+            |(ascii, scripts): &(String, Vec<usize>)| {
+                let unicode: String = ascii
+                    .chars()
+                    .zip(scripts.iter().chain([0].iter().cycle()))
+                    .map(|(c, script)| match c.to_digit(10) {
+                        Some(digit) => char::from_u32(UNICODE_ZEROS[*script % UNICODE_ZEROS.len()] + digit).unwrap(),
+                        None => c,
+                    })
+                    .collect();
+                [Mode::Digits, Mode::Spelled, Mode::Phrases].into_iter().all(|mode| {
+                    let decoder = Decoder::new(mode);
+                    decoder.calibration_value(&unicode) == decoder.calibration_value(ascii)
+                })
+            },
+        );
+    }
+
+    #[test]
+    fn test_ignoring_case() {
+        let decoder = Decoder::new(Mode::Spelled);
+        assert_eq!(decoder.calibration_value("TWOone"), Some(11));
+        assert_eq!(decoder.calibration_value("Eight"), None);
+        let decoder = Decoder::new(Mode::Spelled).ignoring_case();
+        assert_eq!(decoder.calibration_value("TWOone"), Some(21));
+        assert_eq!(decoder.calibration_value("Eight"), Some(88));
+        assert_eq!(decoder.calibration_value("İxSeVeN"), Some(77));
+        let german = Decoder::with_vocabulary(&Vocabulary::builtin("german").unwrap()).ignoring_case();
+        assert_eq!(german.calibration_value("FÜNFundZWANZIG"), Some(55));
+        let phrases = Decoder::new(Mode::Phrases).ignoring_case();
+        assert_eq!(phrases.calibration_value("Forty-Two"), Some(4242));
+        let vocabulary = Vocabulary::parse("EINS = 1\nzwei = 2\nZwei = 2\n").unwrap();
+        assert_eq!(Decoder::with_vocabulary(&vocabulary).calibration_value("eins"), None);
+        let decoder = Decoder::with_vocabulary(&vocabulary).ignoring_case();
+        assert_eq!(decoder.calibration_value("eins"), Some(11));
+        assert_eq!(decoder.calibration_value("xEINSxZWEI"), Some(12));
+    }
+
+    #[test]
+    #[should_panic(expected = "names matching \"eins\" have different values")]
+    fn test_ignoring_case_conflict() {
+        Decoder::with_vocabulary(&Vocabulary::parse("EINS = 1\neins = 2\n").unwrap()).ignoring_case();
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "two1nine\nabc\n٣x, \"seven\"\n";
+        let explanations = Decoder::new(Mode::Spelled).explain(input);
+        assert_eq!(
+            explanations[0].first_and_last,
+            Some((
+                Found { chars: 0..3, token: "two", value: 2 },
+                Found { chars: 4..8, token: "nine", value: 9 }
+            ))
+        );
+        assert_eq!(explanations[0].value(), Some(29));
+        assert_eq!(explanations[1].first_and_last, None);
+        assert_eq!(explanations[2].first_and_last.as_ref().unwrap().1.chars, 5..10);
+
+        let mut table = vec![];
+        write_table(&explanations, &mut table).unwrap();
+        assert_eq!(
+            String::from_utf8(table).unwrap(),
+            "\
+line  first       last               value  text
+   1  0..3 \"two\"  4..8 \"nine\"           29  two1nine
+   2  -           -              no digits  abc
+   3  0..1 \"٣\"    5..10 \"seven\"         37  ٣x, \"seven\"
+sum: 66, 1 line(s) rejected
+"
+        );
+
+        let mut csv = vec![];
+        write_csv(&explanations, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "\
+line,text,first_start,first_end,first_token,last_start,last_end,last_token,value,status
+1,two1nine,0,3,two,4,8,nine,29,ok
+2,abc,,,,,,,,no digits
+3,\"٣x, \"\"seven\"\"\",0,1,٣,5,10,seven,37,ok
+"
+        );
+    }
+
+    #[test]
+    fn test_sum_stream() {
+        let decoder = Decoder::new(Mode::Spelled);
+        for workers in [1, 3] {
+            for chunk_size in [1, 5, 16, 1000] {
+                assert_eq!(sum_stream(TEST_INPUT_2.as_bytes(), &decoder, workers, chunk_size), Ok(281));
+                assert_eq!(sum_stream("two1nine\neight".as_bytes(), &decoder, workers, chunk_size), Ok(117));
+            }
+        }
+        assert_eq!(sum_stream("".as_bytes(), &decoder, 2, 10), Ok(0));
+
+        let error = |input: &[u8], workers| sum_stream(input, &decoder, workers, 4).unwrap_err().to_string();
+        assert_eq!(error(b"1\n2\nthree\nxx\n5\nyy\n", 2), "line 4: no digit in line");
+        assert_eq!(error(b"one\ntwo\n\xff\n", 1), "line 3: invalid UTF-8");
+        let large = "nine hundred ninety-nine million\n".repeat(20);
+        assert_eq!(
+            sum_stream(large.as_bytes(), &Decoder::new(Mode::Phrases), 2, 64).unwrap_err().to_string(),
+            "sum overflows"
+        );
+    }
+
+    #[test]
+    fn test_sum_stream_matches_sum() {
+        use crate::property::check;
+        use crate::rng::Rng;
+
+        check(
+            |rng: &mut Rng| {
+                let lines = rng.vec(0..30, |rng| {
+                    let line: String = rng.vec(0..8, |rng| *rng.choose(&["x", "one", "two", "ne", "é", "3"])).concat();
+                    line + "7"
+                });
+                (lines, (rng.range(1..5), rng.range(1..40)))
+            },
+            |(lines, (workers, chunk_size)): &(Vec<String>, (usize, usize))| {
+                let input = lines.iter().map(|line| format!("{line}\n")).collect::<String>();
+                let decoder = Decoder::new(Mode::Spelled);
+                *workers == 0 || *chunk_size == 0 || sum_stream(input.as_bytes(), &decoder, *workers, *chunk_size) == Ok(decoder.sum(&input))
+            },
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use itertools::Itertools;
+use std::collections::HashSet;
+// use trace::trace;
+
+// trace::init_depth_var!();
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Galaxy {
+    pub(crate) number: usize,
+    x: usize,
+    y: usize,
+}
+
+pub type Universe = HashSet<Galaxy>;
+
+pub fn make_universe(string: &str, expand_factor: usize) -> Universe {
+    let mut galaxy_number = 0;
+    let raw: Vec<Vec<Option<usize>>> = string
+        .split('\n')
+        .filter(|s| s.len() > 1)
+        .map(|line| {
+            line.chars()
+                .map(|c| {
+                    if c == '#' {
+                        galaxy_number += 1;
+                        Some(galaxy_number)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let empty_rows: HashSet<usize> = raw
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.iter().all(|cell| cell.is_none()))
+        .map(|(y, _)| y)
+        .collect();
+    let empty_cols: HashSet<usize> = raw[0]
+        .iter()
+        .enumerate()
+        .filter(|(x, _)| raw.iter().all(|row| row[*x].is_none()))
+        .map(|(x, _)| x)
+        .collect();
+    let mut expanded_y = 0;
+    let mut universe = Universe::new();
+    for (y, row) in raw.iter().enumerate() {
+        if empty_rows.contains(&y) {
+            expanded_y += expand_factor - 1
+        } else {
+            let mut expanded_x = 0;
+            for (x, cell) in row.iter().enumerate() {
+                if empty_cols.contains(&x) {
+                    expanded_x += expand_factor - 1
+                } else if let Some(number) = cell {
+                    universe.insert(Galaxy {
+                        number: *number,
+                        x: expanded_x,
+                        y: expanded_y,
+                    });
+                }
+                expanded_x += 1;
+            }
+        }
+        expanded_y += 1;
+    }
+    universe
+}
+
+fn shortest_path(from: &Galaxy, to: &Galaxy) -> usize {
+    from.x.max(to.x) - from.x.min(to.x) + from.y.max(to.y) - from.y.min(to.y)
+}
+
+pub fn shortest_paths_sum(universe: &Universe) -> usize {
+    universe
+        .iter()
+        .combinations(2)
+        .map(|pair| shortest_path(pair[0], pair[1]))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::check;
+    use crate::rng::Rng;
+
+    static TEST_INPUT_1_1: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_part_1_1() {
+        assert_eq!(shortest_paths_sum(&make_universe(TEST_INPUT_1_1, 2)), 374);
+        assert_eq!(shortest_paths_sum(&make_universe(TEST_INPUT_1_1, 10)), 1030);
+        assert_eq!(shortest_paths_sum(&make_universe(TEST_INPUT_1_1, 100)), 8410);
+    }
+
+    #[test]
+    fn test_shortest_paths_sum_ignores_numbering() {
+        check(
+            |rng| {
+                let width = rng.range(2..10);
+                let grid = rng.vec(1..10, |rng| (0..width).map(|_| rng.chance(0.2)).collect::<Vec<bool>>());
+                (grid, rng.next_u64(), rng.range(1..5))
+            },
+            |(grid, seed, expand_factor): &(Vec<Vec<bool>>, u64, usize)| {
+                if grid.is_empty() || grid.iter().any(|row| row.len() < 2 || row.len() != grid[0].len()) || *expand_factor == 0 {
+                    return true;
+                }
+                let input = grid
+                    .iter()
+                    .map(|row| row.iter().map(|&galaxy| if galaxy { '#' } else { '.' }).collect::<String>())
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let universe = make_universe(&input, *expand_factor);
+                let mut numbers: Vec<usize> = (1..=universe.len()).collect();
+                Rng::new(*seed).shuffle(&mut numbers);
+                let renumbered: Universe = universe
+                    .iter()
+                    .map(|galaxy| Galaxy { number: numbers[galaxy.number - 1], ..*galaxy })
+                    .collect();
+                shortest_paths_sum(&universe) == shortest_paths_sum(&renumbered)
+            },
+        );
+    }
+}
@@ -0,0 +1,849 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+use partial_application::partial;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use smallvec::SmallVec;
+
+use crate::rng::Rng;
+
+/// A cube colour.  Any word can name a colour, regardless of case.  The puzzle's colours need no
+/// allocation, other colours own their name; colours are ordered by name.
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Color(Name);
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+enum Name {
+    Red,
+    Green,
+    Blue,
+    /// Any other lower case name.
+    Other(Arc<str>),
+}
+
+impl Color {
+    pub(crate) fn new(name: &str) -> Color {
+        Color::from_str(name).unwrap()
+    }
+
+    /// Returns the colour for a lower case name.
+    fn named(name: &str) -> Color {
+        Color(match name {
+            "red" => Name::Red,
+            "green" => Name::Green,
+            "blue" => Name::Blue,
+            _ => Name::Other(name.into()),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        match &self.0 {
+            Name::Red => "red",
+            Name::Green => "green",
+            Name::Blue => "blue",
+            Name::Other(name) => name,
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Color, String> {
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+            return Err(format!("invalid colour name {name:?}"));
+        }
+        if name.chars().any(char::is_uppercase) {
+            Ok(Color::named(&name.to_lowercase()))
+        } else {
+            Ok(Color::named(name))
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Debug for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Color({:?})", self.name())
+    }
+}
+
+impl PartialOrd for Color {
+    fn partial_cmp(&self, other: &Color) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Color {
+    fn cmp(&self, other: &Color) -> std::cmp::Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+/// The cubes in the bag.  Colours missing from the bag have no cubes.
+pub type Bag = HashMap<Color, usize>;
+
+/// The cubes of each colour in a draw.  Each colour appears only once.
+pub type Draw = SmallVec<[(Color, usize); 3]>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    id: usize,
+    #[serde(with = "draws_json")]
+    draws: SmallVec<[Draw; 6]>,
+}
+
+impl Game {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// Writes the game like it is read, e.g. "Game 1: 3 blue, 4 red; 1 red, 2 green".
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (index, draw) in self.draws.iter().enumerate() {
+            write!(f, "{}", if index == 0 { " " } else { "; " })?;
+            for (index, (color, count)) in draw.iter().enumerate() {
+                write!(f, "{}{count} {color}", if index == 0 { "" } else { ", " })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Color::from_str(&name).map_err(de::Error::custom)
+    }
+}
+
+/// Draws in JSON are objects from colour to count, like `{"blue": 3, "red": 4}`.  The order of
+/// the colours is kept.
+mod draws_json {
+    use super::*;
+
+    struct DrawJson<'a>(&'a Draw);
+
+    impl Serialize for DrawJson<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.0.len()))?;
+            for (color, count) in self.0 {
+                map.serialize_entry(color, count)?;
+            }
+            map.end()
+        }
+    }
+
+    pub(super) fn serialize<S: Serializer>(draws: &[Draw], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(draws.iter().map(DrawJson))
+    }
+
+    struct DrawVisitor;
+
+    impl<'de> Visitor<'de> for DrawVisitor {
+        type Value = Draw;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an object of colours and counts")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Draw, A::Error> {
+            let mut draw = Draw::new();
+            while let Some((color, count)) = map.next_entry::<Color, usize>()? {
+                if draw.iter().any(|(other, _)| *other == color) {
+                    return Err(de::Error::custom(format!("{color} given more than once")));
+                }
+                draw.push((color, count));
+            }
+            if draw.is_empty() {
+                return Err(de::Error::custom("empty draw"));
+            }
+            Ok(draw)
+        }
+    }
+
+    struct DrawsVisitor;
+
+    impl<'de> Visitor<'de> for DrawsVisitor {
+        type Value = SmallVec<[Draw; 6]>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a list of draws")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<SmallVec<[Draw; 6]>, A::Error> {
+            let mut draws = SmallVec::new();
+            while let Some(DrawSeed(draw)) = seq.next_element()? {
+                draws.push(draw);
+            }
+            if draws.is_empty() {
+                return Err(de::Error::custom("game without draws"));
+            }
+            Ok(draws)
+        }
+    }
+
+    struct DrawSeed(Draw);
+
+    impl<'de> Deserialize<'de> for DrawSeed {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DrawSeed, D::Error> {
+            deserializer.deserialize_map(DrawVisitor).map(DrawSeed)
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SmallVec<[Draw; 6]>, D::Error> {
+        deserializer.deserialize_seq(DrawsVisitor)
+    }
+}
+
+/// Writes the games as a JSON array, one game per line.
+pub fn games_to_json(games: &[Game]) -> String {
+    let games: Vec<String> = games.iter().map(|game| serde_json::to_string(game).unwrap()).collect();
+    format!("[\n{}\n]\n", games.join(",\n"))
+}
+
+/// Reads games written by `games_to_json`, or any other JSON array of the same structure.
+pub fn games_from_json(json: &str) -> Result<Vec<Game>, String> {
+    serde_json::from_str(json).map_err(|error| error.to_string())
+}
+
+/// Reads a game line byte by byte.
+struct Parser<'a> {
+    line: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, expected: &str) -> Result<T, String> {
+        Err(format!("expected {expected} at column {} of {:?}", self.position + 1, self.line))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.line.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_spaces(&mut self) -> bool {
+        let start = self.position;
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+        self.position > start
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<(), String> {
+        if self.line.as_bytes()[self.position..].starts_with(literal.as_bytes()) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            self.error(&format!("{literal:?}"))
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, String> {
+        let start = self.position;
+        let mut number: usize = 0;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            number = match number.checked_mul(10).and_then(|number| number.checked_add((digit - b'0') as usize)) {
+                Some(number) => number,
+                None => return self.error("a smaller number"),
+            };
+            self.position += 1;
+        }
+        if self.position == start {
+            return self.error("a number");
+        }
+        Ok(number)
+    }
+
+    fn color(&mut self) -> Result<Color, String> {
+        let start = self.position;
+        while self.peek().is_some_and(|byte| !byte.is_ascii_whitespace() && byte != b',' && byte != b';') {
+            self.position += 1;
+        }
+        if self.position == start {
+            return self.error("a colour");
+        }
+        let name = &self.line[start..self.position];
+        if name.bytes().any(|byte| byte.is_ascii_uppercase()) || !name.is_ascii() {
+            Color::from_str(name)
+        } else {
+            Ok(Color::named(name))
+        }
+    }
+
+    fn game(&mut self) -> Result<Game, String> {
+        self.literal("Game")?;
+        if !self.skip_spaces() {
+            return self.error("a space");
+        }
+        let id = self.number()?;
+        self.literal(":")?;
+        let mut draws = SmallVec::new();
+        let mut draw = Draw::new();
+        loop {
+            self.skip_spaces();
+            let count = self.number()?;
+            if !self.skip_spaces() {
+                return self.error("a space");
+            }
+            let color = self.color()?;
+            // Counts of a colour named more than once in a draw add up.
+            add_cubes(&mut draw, color, count);
+            self.skip_spaces();
+            match self.peek() {
+                Some(b',') => {}
+                Some(b';') => draws.push(std::mem::take(&mut draw)),
+                None => break,
+                Some(_) => return self.error("\",\", \";\" or the end of the line"),
+            }
+            self.position += 1;
+        }
+        draws.push(draw);
+        Ok(Game { id, draws })
+    }
+}
+
+/// Parses a line like "Game 1: 3 blue, 4 red; 1 red, 2 green", returning an error message if
+/// the line is not in that format.
+pub fn try_parse_game(line: &str) -> Result<Game, String> {
+    Parser { line, position: 0 }.game()
+}
+
+pub fn parse_game(input_string: &str) -> Game {
+    try_parse_game(input_string).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Parses the games of the input one at a time.
+pub fn parse_games(input: &str) -> impl Iterator<Item = Game> + '_ {
+    input.lines().map(parse_game)
+}
+
+fn add_cubes(draw: &mut Draw, color: Color, count: usize) {
+    match draw.iter_mut().find(|(other, _)| *other == color) {
+        Some((_, total)) => *total += count,
+        None => draw.push((color, count)),
+    }
+}
+
+#[cfg(test)]
+fn count(draw: &Draw, color: Color) -> usize {
+    draw.iter().find(|(other, _)| *other == color).map_or(0, |(_, count)| *count)
+}
+
+fn draw_impossible(bag: &Bag, draw: &Draw) -> bool {
+    draw.iter().any(|(color, count)| bag.get(color).copied().unwrap_or(0) < *count)
+}
+
+/// Whether the cubes of a draw are put back into the bag before the next draw of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawMode {
+    WithReplacement,
+    /// The cubes drawn stay out of the bag until the game ends.
+    WithoutReplacement,
+}
+
+/// Returns whether the game could have been played with the bag.  Colours in the bag that do
+/// not appear in the game do not matter.
+pub fn game_possible(bag: &Bag, game: &Game, mode: DrawMode) -> bool {
+    match mode {
+        DrawMode::WithReplacement => game.draws.iter()
+            .filter(partial!(draw_impossible => bag, _))
+            .next().is_none(),
+        DrawMode::WithoutReplacement => {
+            let mut drawn = Draw::new();
+            for (color, count) in game.draws.iter().flatten() {
+                add_cubes(&mut drawn, color.clone(), *count);
+            }
+            !draw_impossible(bag, &drawn)
+        }
+    }
+}
+
+pub fn default_bag() -> Bag {
+    HashMap::from([(Color::new("red"), 12), (Color::new("green"), 13), (Color::new("blue"), 14)])
+}
+
+/// Parses a bag like "red=12,green=13,blue=14".
+pub fn parse_bag(spec: &str) -> Result<Bag, String> {
+    let mut bag = Bag::new();
+    for part in spec.split(',') {
+        let Some((color, count)) = part.split_once('=') else {
+            return Err(format!("expected \"<colour>=<count>\" instead of {part:?}"));
+        };
+        let color = Color::from_str(color.trim())?;
+        let count = count.trim().parse().map_err(|_| format!("invalid count {count:?} for {color}"))?;
+        if bag.insert(color.clone(), count).is_some() {
+            return Err(format!("{color} given more than once"));
+        }
+    }
+    Ok(bag)
+}
+
+/// Reads bags from a file, one per line in the format of `parse_bag`.  Blank lines and lines
+/// starting with `#` are ignored.
+pub fn load_bags(filename: &str) -> Result<Vec<Bag>, String> {
+    let text = read_to_string(filename).map_err(|error| format!("{filename}: {error}"))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| parse_bag(line).map_err(|error| format!("{filename}:{}: {error}", index + 1)))
+        .collect()
+}
+
+/// Formats a bag like `parse_bag` expects it, with the colours sorted.
+pub fn format_bag(bag: &Bag) -> String {
+    let mut colors: Vec<(&Color, &usize)> = bag.iter().collect();
+    colors.sort();
+    colors.iter().map(|(color, count)| format!("{color}={count}")).collect::<Vec<String>>().join(",")
+}
+
+/// Returns the ids of the games that are possible with the bag.
+pub fn possible_games(bag: &Bag, games: &[Game], mode: DrawMode) -> Vec<usize> {
+    games
+        .iter()
+        .filter(partial!(game_possible => bag, _, mode))
+        .map(|game| game.id)
+        .collect()
+}
+
+pub fn part_one(input: &str) -> usize {
+    let bag = default_bag();
+    parse_games(input)
+        .filter(partial!(game_possible => &bag, _, DrawMode::WithReplacement))
+        .map(| game | game.id)
+        .sum()
+}
+
+/// The smallest number of cubes of a colour that makes all games possible, and the first draw
+/// that needs that many.
+#[derive(Debug, PartialEq)]
+pub struct ColorMinimum {
+    pub color: Color,
+    pub count: usize,
+    pub game: usize,
+    /// Position of the draw in the game, counting from 1.
+    pub draw: usize,
+}
+
+/// Returns the smallest bag with which all games are possible, by colour.
+pub fn minimal_bag(games: &[Game]) -> Vec<ColorMinimum> {
+    let mut minimums: HashMap<Color, ColorMinimum> = HashMap::new();
+    for game in games {
+        for (index, draw) in game.draws.iter().enumerate() {
+            for &(ref color, count) in draw {
+                let minimum = minimums.entry(color.clone()).or_insert(ColorMinimum {
+                    color: color.clone(),
+                    count,
+                    game: game.id,
+                    draw: index + 1,
+                });
+                if minimum.count < count {
+                    (minimum.count, minimum.game, minimum.draw) = (count, game.id, index + 1);
+                }
+            }
+        }
+    }
+    let mut minimums: Vec<ColorMinimum> = minimums.into_values().collect();
+    minimums.sort_by(|a, b| a.color.cmp(&b.color));
+    minimums
+}
+
+/// The product of the minimum number of cubes of each colour needed for the game.  Colours that
+/// are never drawn, or only with a count of 0, do not count.
+pub fn power(game: Game) -> usize {
+    let mut min_counts = Draw::new();
+    for (color, draw_count) in game.draws.into_iter().flatten() {
+        match min_counts.iter_mut().find(|(other, _)| *other == color) {
+            Some((_, min_count)) => *min_count = draw_count.max(*min_count),
+            None => min_counts.push((color, draw_count)),
+        }
+    }
+    min_counts.iter().map(|(_, count)| count.max(&1)).product()
+}
+
+/// An estimate of the number of cubes of a colour in the bag.
+#[derive(Debug, PartialEq)]
+pub struct ColorEstimate {
+    pub color: Color,
+    /// The maximum likelihood estimate.
+    pub estimate: usize,
+    /// The bag holds no more cubes than this with the requested confidence.
+    pub upper_bound: usize,
+}
+
+/// Estimates the bag the games were played with, assuming that the number of cubes of each
+/// colour in a draw is uniformly distributed between 0 and the number in the bag, independent
+/// of other colours and draws.  A colour missing from a draw counts as 0 drawn.
+///
+/// With `n` cubes in the bag, the probability that the largest of `d` draws is at most `m` is
+/// `((m + 1) / (n + 1))^d`.  This is largest for `n = m`, so the highest count drawn is the
+/// maximum likelihood estimate.  The upper bound is the largest `n` for which that probability
+/// is at least `1 - confidence`.
+pub fn estimate_bag(games: &[Game], confidence: f64) -> Vec<ColorEstimate> {
+    assert!(confidence > 0.0 && confidence < 1.0, "confidence must be between 0 and 1");
+    let draws = games.iter().map(|game| game.draws.len()).sum::<usize>() as f64;
+    minimal_bag(games)
+        .into_iter()
+        .map(|minimum| {
+            let bound = (minimum.count + 1) as f64 / (1.0 - confidence).powf(1.0 / draws) - 1.0;
+            ColorEstimate {
+                color: minimum.color,
+                estimate: minimum.count,
+                upper_bound: (bound.floor() as usize).max(minimum.count),
+            }
+        })
+        .collect()
+}
+
+pub fn part_two(input: &str) -> usize {
+    parse_games(input)
+        .map(power)
+        .sum()
+}
+
+/// Generated games together with what is known about them.
+#[derive(Debug)]
+pub struct GameLog {
+    /// The games in the puzzle input format, one per line.
+    pub input: String,
+    /// Whether each game is possible with the bag it was generated from, by game.
+    pub possible: Vec<bool>,
+    /// The sum of the ids of the possible games, which `part_one` returns for the puzzle bag.
+    pub id_sum: usize,
+    /// The sum of the game powers, which `part_two` returns.
+    pub power_sum: usize,
+}
+
+/// Generates `games` games drawn from `bag` with replacement, with a number of draws per game
+/// in `draws`.  Each draw is made impossible with probability `impossible_rate`, by drawing
+/// more cubes of one colour than the bag holds.  The same seed always generates the same log.
+pub fn generate_games(seed: u64, bag: &Bag, games: usize, draws: Range<usize>, impossible_rate: f64) -> GameLog {
+    assert!(draws.start > 0, "games need at least one draw");
+    let mut colors: Vec<(Color, usize)> = bag.iter().filter(|(_, count)| **count > 0).map(|(color, count)| (color.clone(), *count)).collect();
+    assert!(!colors.is_empty(), "no cubes in the bag");
+    colors.sort();
+
+    let mut rng = Rng::new(seed);
+    let mut log = GameLog { input: String::new(), possible: vec![], id_sum: 0, power_sum: 0 };
+    for id in 1..=games {
+        let mut game = Game { id, draws: SmallVec::new() };
+        let mut possible = true;
+        let mut maximums = Draw::new();
+        for _ in 0..rng.range(draws.clone()) {
+            rng.shuffle(&mut colors);
+            let mut draw: Draw = colors[..rng.range(1..colors.len() + 1)]
+                .iter()
+                .map(|(color, count)| (color.clone(), rng.range(1..count + 1)))
+                .collect();
+            if rng.chance(impossible_rate) {
+                let index = rng.range(0..draw.len());
+                let (color, count) = &mut draw[index];
+                *count = bag[color] + rng.range(1..6);
+                possible = false;
+            }
+            for (color, count) in &draw {
+                match maximums.iter_mut().find(|(other, _)| other == color) {
+                    Some((_, maximum)) => *maximum = (*count).max(*maximum),
+                    None => maximums.push((color.clone(), *count)),
+                }
+            }
+            game.draws.push(draw);
+        }
+        log.input.push_str(&format!("{game}\n"));
+        log.possible.push(possible);
+        log.id_sum += if possible { id } else { 0 };
+        log.power_sum += maximums.iter().map(|(_, count)| count).product::<usize>();
+    }
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_parse_game() {
+        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 3 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
+        assert_eq!(game.id, 18);
+        assert_eq!(game.draws.len(), 6);
+        assert_eq!(count(&game.draws[1], Color::new("red")), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_input() {
+        parse_game("foo");
+    }
+
+    #[test]
+    fn test_game_possible() {
+        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 3 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
+        assert!(game_possible(&default_bag(), &game, DrawMode::WithReplacement))
+    }
+
+    #[test]
+    fn test_game_impossible() {
+        let game = parse_game("Game 18: 3 red; 4 red, 1 blue; 3 green, 30 red; 10 green, 1 blue; 4 red, 6 green, 1 blue; 3 green");
+        assert!(!game_possible(&default_bag(), &game, DrawMode::WithReplacement));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |line| try_parse_game(line).unwrap_err();
+        assert_eq!(error("Game one: 1 red"), "expected a number at column 6 of \"Game one: 1 red\"");
+        assert_eq!(error("Game 1 3 red"), "expected \":\" at column 7 of \"Game 1 3 red\"");
+        assert_eq!(error("Game 1: 3 red;"), "expected a number at column 15 of \"Game 1: 3 red;\"");
+        assert_eq!(error("Game 1: 3red"), "expected a space at column 10 of \"Game 1: 3red\"");
+        assert_eq!(error("Game 1: 3 red 4 blue"), "expected \",\", \";\" or the end of the line at column 15 of \"Game 1: 3 red 4 blue\"");
+        assert_eq!(error("Game 1: 99999999999999999999 red"), "expected a smaller number at column 28 of \"Game 1: 99999999999999999999 red\"");
+        assert_eq!(error(""), "expected \"Game\" at column 1 of \"\"");
+
+        let game = parse_game("Game  12:3 red ,1 Blue;  2 green ");
+        assert_eq!(game.id, 12);
+        assert_eq!(game.draws.len(), 2);
+        assert_eq!(count(&game.draws[0], Color::new("blue")), 1);
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "Game 18: 3 red; 4 red, 1 blue; 3 green, 3 red";
+        assert_eq!(parse_game(line).to_string(), line);
+        assert_eq!(parse_game("Game  7:1 Red,2 red ;  4 BLUE ").to_string(), "Game 7: 3 red; 4 blue");
+    }
+
+    #[test]
+    fn test_json() {
+        let games = [parse_game("Game 1: 3 blue, 4 red; 1 red"), parse_game("Game 2: 5 teal")];
+        let json = games_to_json(&games);
+        assert_eq!(
+            json,
+            "[\n{\"id\":1,\"draws\":[{\"blue\":3,\"red\":4},{\"red\":1}]},\n{\"id\":2,\"draws\":[{\"teal\":5}]}\n]\n"
+        );
+        assert_eq!(games_from_json(&json).unwrap(), games);
+        assert_eq!(
+            games_from_json(r#"[{"draws": [{"Red": 2, "gold": 1}], "id": 4}]"#).unwrap()[0].to_string(),
+            "Game 4: 2 red, 1 gold"
+        );
+
+        let error = |json| games_from_json(json).unwrap_err();
+        assert!(error(r#"[{"id": 1, "draws": []}]"#).starts_with("game without draws"));
+        assert!(error(r#"[{"id": 1, "draws": [{}]}]"#).starts_with("empty draw"));
+        assert!(error(r#"[{"id": 1, "draws": [{"red": 1, "RED": 2}]}]"#).starts_with("red given more than once"));
+        assert!(error(r#"[{"id": 1, "draws": [{"red": -1}]}]"#).starts_with("invalid value"));
+        assert!(error(r#"[{"id": 1, "draws": [{"dark red": 1}]}]"#).starts_with("invalid colour name"));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        use crate::property::check;
+        use crate::rng::Rng;
+
+        let names = ["red", "Green", "BLUE", "teal", "violet"];
+        check(
+            |rng: &mut Rng| {
+                let id = rng.range(0..1000);
+                let draws = rng.vec(1..5, |rng| {
+                    let mut colors = names;
+                    rng.shuffle(&mut colors);
+                    let cubes = colors[..rng.range(1..5)].iter().map(|color| format!("{} {color}", rng.range(0..30)));
+                    cubes.collect::<Vec<String>>().join(", ")
+                });
+                format!("Game {id}: {}", draws.join("; "))
+            },
+            |line: &String| {
+                let game = parse_game(line);
+                let text = game.to_string();
+                let from_json = games_from_json(&games_to_json(std::slice::from_ref(&game))).unwrap();
+                text.eq_ignore_ascii_case(line) && parse_game(&text) == game && from_json == [game]
+            },
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        assert_eq!(Color::from_str("red").unwrap(), Color::new("red"));
+        assert_eq!(Color::from_str("Green").unwrap(), Color::new("green"));
+        assert_eq!(Color::from_str("BLUE").unwrap().to_string(), "blue");
+        assert!(Color::from_str("").is_err());
+        assert!(Color::from_str("red,").is_err());
+    }
+
+    #[test]
+    fn test_other_colors() {
+        let game = parse_game("Game 7: 2 violet, 1 red; 3 Violet, 4 teal; 1 teal, 1 teal");
+        assert_eq!(count(&game.draws[2], Color::new("teal")), 2);
+        assert_eq!(power(game), 12);
+
+        // Colours missing from the bag have no cubes, extra colours in the bag do not matter.
+        let game = parse_game("Game 8: 2 violet, 1 red; 0 teal");
+        let bag = HashMap::from([(Color::new("violet"), 2), (Color::new("red"), 1), (Color::new("gold"), 5)]);
+        assert!(game_possible(&bag, &game, DrawMode::WithReplacement));
+        assert!(!game_possible(&default_bag(), &game, DrawMode::WithReplacement));
+        assert_eq!(power(game), 2);
+    }
+
+    #[test]
+    fn test_draw_modes() {
+        let bag = parse_bag("red=5,blue=3").unwrap();
+        let possible = |game, mode| game_possible(&bag, &parse_game(game), mode);
+        // Every draw fits, but together they take more cubes than the bag holds.
+        assert!(possible("Game 1: 3 red; 3 red", DrawMode::WithReplacement));
+        assert!(!possible("Game 1: 3 red; 3 red", DrawMode::WithoutReplacement));
+        assert!(possible("Game 2: 2 red, 1 blue; 3 red, 2 blue", DrawMode::WithoutReplacement));
+        assert!(!possible("Game 3: 2 red, 1 blue; 3 red, 2 blue; 1 blue", DrawMode::WithoutReplacement));
+        assert!(possible("Game 3: 2 red, 1 blue; 3 red, 2 blue; 1 blue", DrawMode::WithReplacement));
+        // A draw that does not fit on its own is impossible either way.
+        assert!(!possible("Game 4: 6 red", DrawMode::WithReplacement));
+        assert!(!possible("Game 4: 6 red", DrawMode::WithoutReplacement));
+    }
+
+    #[test]
+    fn test_without_replacement_is_stricter() {
+        use crate::property::check;
+
+        check(
+            |rng| {
+                let bag = (rng.range(0..8), rng.range(0..8));
+                (bag, rng.vec(1..5, |rng| (rng.range(0..6), rng.range(0..6))))
+            },
+            |((red, blue), draws): &((usize, usize), Vec<(usize, usize)>)| {
+                let bag = parse_bag(&format!("red={red},blue={blue}")).unwrap();
+                let draws = draws.iter().map(|(red, blue)| format!("{red} red, {blue} blue")).collect::<Vec<String>>();
+                let game = parse_game(&format!("Game 1: {}", draws.join("; ")));
+                !game_possible(&bag, &game, DrawMode::WithoutReplacement) || game_possible(&bag, &game, DrawMode::WithReplacement)
+            },
+        );
+    }
+
+    #[test]
+    fn test_minimal_bag() {
+        let games: Vec<Game> = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+        ]
+        .into_iter()
+        .map(parse_game)
+        .collect();
+        let minimum = |color: &str, count, game, draw| ColorMinimum { color: Color::new(color), count, game, draw };
+        assert_eq!(
+            minimal_bag(&games),
+            vec![minimum("blue", 15, 4, 3), minimum("green", 13, 3, 2), minimum("red", 20, 3, 1)]
+        );
+        assert_eq!(minimal_bag(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_estimate_bag() {
+        // Play games with random bags under the sampling model and count how often the bound
+        // holds.
+        let mut rng = Rng::new(40);
+        let trials = 400;
+        let mut covered = 0;
+        for _ in 0..trials {
+            let bag: Vec<(String, usize)> = ["red", "green", "blue"].iter().map(|color| (color.to_string(), rng.range(1..30))).collect();
+            let games: Vec<Game> = (1..=rng.range(1..6))
+                .map(|id| {
+                    let draws = rng.vec(1..4, |rng| {
+                        let cubes = bag.iter().map(|(color, count)| format!("{} {color}", rng.range(0..count + 1)));
+                        cubes.collect::<Vec<String>>().join(", ")
+                    });
+                    parse_game(&format!("Game {id}: {}", draws.join("; ")))
+                })
+                .collect();
+            let estimates = estimate_bag(&games, 0.9);
+            for (estimate, (_, count)) in estimates.iter().zip(bag.iter().sorted_unstable_by_key(|(color, _)| color.clone())) {
+                assert!(estimate.estimate <= *count && estimate.estimate <= estimate.upper_bound);
+                covered += (estimate.upper_bound >= *count) as usize;
+            }
+        }
+        let coverage = covered as f64 / (trials * 3) as f64;
+        assert!((0.85..=0.95).contains(&coverage), "coverage {coverage}");
+
+        let games = [parse_game("Game 1: 4 red; 4 red")];
+        assert_eq!(
+            estimate_bag(&games, 0.75),
+            vec![ColorEstimate { color: Color::new("red"), estimate: 4, upper_bound: 9 }]
+        );
+    }
+
+    #[test]
+    fn test_bags() {
+        let games: Vec<Game> = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ]
+        .into_iter()
+        .map(parse_game)
+        .collect();
+        assert_eq!(possible_games(&parse_bag("red=12,green=13,blue=14").unwrap(), &games, DrawMode::WithReplacement), vec![1, 2, 5]);
+        assert_eq!(possible_games(&parse_bag("red=20, green=13, blue=15").unwrap(), &games, DrawMode::WithReplacement), vec![1, 2, 3, 4, 5]);
+        assert_eq!(possible_games(&parse_bag("Red=6,Blue=6,green=3").unwrap(), &games, DrawMode::WithReplacement), vec![1, 2, 5]);
+        assert_eq!(possible_games(&parse_bag("red=100").unwrap(), &games, DrawMode::WithReplacement), Vec::<usize>::new());
+
+        assert_eq!(format_bag(&parse_bag("red=12,green=13,blue=14").unwrap()), "blue=14,green=13,red=12");
+        assert_eq!(parse_bag("red:12").unwrap_err(), "expected \"<colour>=<count>\" instead of \"red:12\"");
+        assert_eq!(parse_bag("red=x").unwrap_err(), "invalid count \"x\" for red");
+        assert_eq!(parse_bag("red=1,RED=2").unwrap_err(), "red given more than once");
+    }
+
+    #[test]
+    fn test_generate_games() {
+        let bag = default_bag();
+        let log = generate_games(44, &bag, 500, 1..7, 0.1);
+        let games: Vec<Game> = parse_games(&log.input).collect();
+        assert_eq!(games.len(), 500);
+        assert!(games.iter().all(|game| (1..7).contains(&game.draws.len())));
+        let possible: Vec<bool> = games.iter().map(|game| game_possible(&bag, game, DrawMode::WithReplacement)).collect();
+        assert_eq!(possible, log.possible);
+        assert_eq!(part_one(&log.input), log.id_sum);
+        assert_eq!(part_two(&log.input), log.power_sum);
+        // With one to six draws per game and 10% impossible draws, about 30% of the games are impossible.
+        let impossible = log.possible.iter().filter(|possible| !**possible).count();
+        assert!((100..200).contains(&impossible), "{impossible} impossible games");
+
+        assert_eq!(generate_games(44, &bag, 500, 1..7, 0.1).input, log.input);
+        assert!(generate_games(1, &bag, 100, 1..3, 0.0).possible.iter().all(|possible| *possible));
+        assert!(generate_games(1, &bag, 100, 1..3, 1.0).possible.iter().all(|possible| !*possible));
+        let log = generate_games(2, &parse_bag("teal=3,gold=0").unwrap(), 10, 2..3, 0.0);
+        assert!(log.input.lines().all(|line| line.contains("teal") && !line.contains("gold")));
+    }
+
+    #[test]
+    fn test_part_powers() {
+        let sample_data = [
+            ("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green", 48),
+            ("Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue", 12),
+            ("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red", 1560),
+            ("Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red", 630),
+            ("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green", 36),];
+        for (game_string, pow) in sample_data {
+            let game = parse_game(game_string);
+            assert_eq!(power(game), pow);
+        }
+    }
+}
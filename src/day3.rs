@@ -0,0 +1,772 @@
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::ops::Range;
+
+/// A part number in the schematic.  `row` and `cols` are coordinates in the padded input data,
+/// so the first character of the input is at row 1, column 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub value: usize,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+
+/// How many parts must be next to a gear symbol for it to be a gear.  A gear needs at least one
+/// part, so a count of 0 is rejected when the schematic is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeighbourCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl NeighbourCount {
+    fn matches(self, count: usize) -> bool {
+        match self {
+            NeighbourCount::Exactly(required) => count == required,
+            NeighbourCount::AtLeast(required) => count >= required,
+        }
+    }
+
+    fn required(self) -> usize {
+        match self {
+            NeighbourCount::Exactly(required) | NeighbourCount::AtLeast(required) => required,
+        }
+    }
+}
+
+/// How the values of the parts next to a gear make up its ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+}
+
+/// Which characters are symbols and gears.  Every character that is neither a digit nor a
+/// blank is a symbol.  The default rules are the puzzle's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub gears: Vec<char>,
+    pub neighbours: NeighbourCount,
+    pub combine: Combine,
+    /// The first blank is also used for the border around the input.
+    pub blanks: Vec<char>,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            gears: vec!['*'],
+            neighbours: NeighbourCount::Exactly(2),
+            combine: Combine::Product,
+            blanks: vec!['.'],
+        }
+    }
+}
+
+impl Rules {
+    fn is_symbol(&self, c: char) -> bool {
+        !c.is_ascii_digit() && !self.blanks.contains(&c)
+    }
+}
+
+#[derive(Debug)]
+pub struct MotorSchematic {
+    rules: Rules,
+    input_data: Vec<Vec<char>>,
+    /// The part numbers in reading order.
+    part_numbers: Vec<Part>,
+    /// The position of the part in `part_numbers` for each cell that is a digit of a part number.
+    part_number_index: Vec<Vec<Option<usize>>>,
+    /// The results of `find_and_sum_part_numbers` and `find_and_sum_gear_ratios`, computed when
+    /// first asked for and then kept up to date by `set_cell`.
+    part_sum: OnceCell<usize>,
+    gear_ratio_sum: OnceCell<usize>,
+}
+
+impl MotorSchematic {
+    /// Returns the part numbers in reading order, also after edits.
+    pub fn parts(&self) -> &[Part] {
+        &self.part_numbers
+    }
+
+    pub fn part_sum(&self) -> usize {
+        *self.part_sum.get_or_init(|| find_and_sum_part_numbers(self))
+    }
+
+    pub fn gear_ratio_sum(&self) -> usize {
+        *self.gear_ratio_sum.get_or_init(|| find_and_sum_gear_ratios(self))
+    }
+
+    /// Returns the rows of the schematic without the border.
+    pub fn lines(&self) -> Vec<String> {
+        let height = self.input_data.len();
+        self.input_data[1..height - 1]
+            .iter()
+            .map(|row| row[1..row.len() - 1].iter().collect())
+            .collect()
+    }
+}
+
+/// What to do with rows that are not as long as the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaggedRows {
+    Reject,
+    /// Pads all rows with blanks to the length of the longest.
+    Pad,
+}
+
+/// Reads a schematic with the puzzle's rules.  Panics if the rows are not all of the same length.
+pub fn read_motor_schematic(lines: &[String]) -> MotorSchematic
+{
+    read_motor_schematic_with_rules(lines, Rules::default())
+}
+
+pub fn read_motor_schematic_with_rules(lines: &[String], rules: Rules) -> MotorSchematic
+{
+    try_read_motor_schematic(lines, rules, RaggedRows::Reject).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Reads a schematic, or returns an error naming the first ragged row if they are rejected.  An
+/// empty schematic has no parts and no gears.
+pub fn try_read_motor_schematic(lines: &[String], rules: Rules, ragged: RaggedRows) -> Result<MotorSchematic, String>
+{
+    assert!(!rules.blanks.is_empty(), "no blank characters");
+    assert!(!rules.blanks.iter().any(char::is_ascii_digit), "blanks must not be digits");
+    assert!(rules.gears.iter().all(|&gear| rules.is_symbol(gear)), "gears must be symbols");
+    assert!(rules.neighbours.required() > 0, "gears need at least one neighbouring part");
+
+    let lengths: Vec<usize> = lines.iter().map(|line| line.chars().count()).collect();
+    let line_width = match ragged {
+        RaggedRows::Reject => {
+            let expected = lengths.first().copied().unwrap_or(0);
+            if let Some(index) = lengths.iter().position(|&length| length != expected) {
+                return Err(format!(
+                    "line {} is {} characters long instead of {expected} like the first line",
+                    index + 1,
+                    lengths[index]
+                ));
+            }
+            expected
+        }
+        RaggedRows::Pad => lengths.iter().copied().max().unwrap_or(0),
+    };
+
+    // Read the input data into a two-dimensional "array" that has a one character around the
+    // border so that we can safely check adjacency without worrying about the edges.
+    let width = line_width + 2;
+    let height = lines.len() + 2;
+
+    let mut input_data = vec![vec![rules.blanks[0]; width]; height];
+
+    for (index, line) in lines.iter().enumerate() {
+        let row = &mut input_data[index + 1];
+        let chars = line.chars().collect::<Vec<char>>();
+        row.splice(1..chars.len() + 1, chars);
+    }
+
+    // Find the part numbers, building an index from (expanded) input coordinates to part
+    // number ids and a list of all part numbers.  Numbers that appear more than once are
+    // separate parts.
+    let mut part_number_index = vec![vec![None; width]; height];
+    let mut part_numbers: Vec<Part> = vec![];
+
+    for (row, row_data) in input_data.iter().enumerate() {
+        let mut number_start_col = 0;
+        let mut current_number: usize = 0;
+        let mut in_number = false;
+        let mut is_part_number = false;
+        for (col, c) in row_data.iter().enumerate() {
+            if c.is_ascii_digit() {
+                if !in_number {
+                    in_number = true;
+                    number_start_col = col;
+                }
+                current_number = current_number * 10 + (*c as usize - '0' as usize);
+                is_part_number = is_part_number || has_adjacent(&input_data, row, col, |c| rules.is_symbol(c))
+            } else {
+                if in_number {
+                    if is_part_number {
+                        for index in &mut part_number_index[row][number_start_col..col] {
+                            *index = Some(part_numbers.len());
+                        }
+                        part_numbers.push(Part { value: current_number, row, cols: number_start_col..col });
+                    }
+                    current_number = 0;
+                    in_number = false;
+                    is_part_number = false;
+                }
+            }
+        }
+    }
+
+    Ok(MotorSchematic {
+        rules,
+        input_data,
+        part_number_index,
+        part_numbers,
+        part_sum: OnceCell::new(),
+        gear_ratio_sum: OnceCell::new(),
+    })
+}
+
+fn has_adjacent<F>(
+    input_data: &[Vec<char>],
+    row: usize,
+    col: usize,
+    predicate: F,
+) -> bool
+    where
+        F: Fn(char) -> bool,
+{
+    assert!(input_data[row][col].is_ascii_digit());
+    row < input_data.len() - 1
+        && col < input_data[0].len() - 1
+        && (predicate(input_data[row - 1][col])
+        || predicate(input_data[row - 1][col - 1])
+        || predicate(input_data[row][col - 1])
+        || predicate(input_data[row + 1][col - 1])
+        || predicate(input_data[row + 1][col])
+        || predicate(input_data[row + 1][col + 1])
+        || predicate(input_data[row][col + 1])
+        || predicate(input_data[row - 1][col + 1]))
+}
+
+/// Returns the ids of the parts next to the cell.
+fn adjacent_part_numbers(schematic: &MotorSchematic, row: usize, col: usize) -> HashSet<usize> {
+    let mut result: HashSet<usize> = HashSet::new();
+    let index = &schematic.part_number_index;
+    for part_number in [
+        index[row - 1][col],
+        index[row - 1][col - 1],
+        index[row][col - 1],
+        index[row + 1][col - 1],
+        index[row + 1][col],
+        index[row + 1][col + 1],
+        index[row][col + 1],
+        index[row - 1][col + 1],
+    ].into_iter().flatten() {
+        result.insert(part_number);
+    }
+    result
+}
+
+pub fn find_and_sum_part_numbers(schematic: &MotorSchematic) -> usize
+{
+    schematic.part_numbers.iter().map(|part| part.value).sum()
+}
+
+/// Returns the ratio of the gear in the cell, or `None` if the cell is not a gear.
+fn gear_ratio(schematic: &MotorSchematic, row: usize, col: usize) -> Option<usize> {
+    if !schematic.rules.gears.contains(&schematic.input_data[row][col]) {
+        return None;
+    }
+    let part_numbers = adjacent_part_numbers(schematic, row, col);
+    if !schematic.rules.neighbours.matches(part_numbers.len()) {
+        return None;
+    }
+    let values = part_numbers.iter().map(|id| schematic.part_numbers[*id].value);
+    Some(match schematic.rules.combine {
+        Combine::Product => values.product(),
+        Combine::Sum => values.sum(),
+    })
+}
+
+pub fn find_and_sum_gear_ratios(schematic: &MotorSchematic) -> usize
+{
+    let mut sum: usize = 0;
+    for (row, row_data) in schematic.input_data.iter().enumerate() {
+        for col in 0..row_data.len() {
+            sum += gear_ratio(schematic, row, col).unwrap_or(0);
+        }
+    }
+    sum
+}
+
+/// Returns the columns of the number that the cell is a digit of.
+fn number_at(row_data: &[char], col: usize) -> Option<Range<usize>> {
+    if !row_data[col].is_ascii_digit() {
+        return None;
+    }
+    let start = (0..col).rev().take_while(|&col| row_data[col].is_ascii_digit()).last().unwrap_or(col);
+    let end = (col..row_data.len()).find(|&col| !row_data[col].is_ascii_digit()).unwrap();
+    Some(start..end)
+}
+
+impl MotorSchematic {
+    /// Returns the numbers with a digit next to or in the cell, as (row, columns).
+    fn numbers_around(&self, row: usize, col: usize) -> Vec<(usize, Range<usize>)> {
+        let mut numbers: Vec<(usize, Range<usize>)> = vec![];
+        for row in row - 1..=row + 1 {
+            for col in col - 1..=col + 1 {
+                if let Some(cols) = number_at(&self.input_data[row], col) {
+                    if !numbers.contains(&(row, cols.clone())) {
+                        numbers.push((row, cols));
+                    }
+                }
+            }
+        }
+        numbers
+    }
+
+    /// Removes the part, leaving the index of the parts after it to `reindex`.
+    fn remove_part(&mut self, id: usize) {
+        let part = self.part_numbers.remove(id);
+        if let Some(sum) = self.part_sum.get_mut() {
+            *sum -= part.value;
+        }
+        self.part_number_index[part.row][part.cols].fill(None);
+    }
+
+    /// Inserts the part in reading order and returns its id, leaving the index to `reindex`.
+    fn insert_part(&mut self, part: Part) -> usize {
+        let id = self.part_numbers.partition_point(|other| (other.row, other.cols.start) < (part.row, part.cols.start));
+        if let Some(sum) = self.part_sum.get_mut() {
+            *sum += part.value;
+        }
+        self.part_numbers.insert(id, part);
+        id
+    }
+
+    /// Updates the index for the parts from `first` on, whose ids have changed.
+    fn reindex(&mut self, first: usize) {
+        for (id, part) in self.part_numbers.iter().enumerate().skip(first) {
+            self.part_number_index[part.row][part.cols.clone()].fill(Some(id));
+        }
+    }
+
+    /// Changes the cell at `row` and `col`, counting from 1 like `Part`, and updates the parts
+    /// and the sums that have been computed.  Only the parts and gears near the cell are looked
+    /// at, but the ids of the parts after the changed ones are shifted to keep reading order.
+    pub fn set_cell(&mut self, row: usize, col: usize, c: char) {
+        let height = self.input_data.len();
+        let width = self.input_data[0].len();
+        assert!((1..height - 1).contains(&row) && (1..width - 1).contains(&col), "{row}:{col} is outside the schematic");
+
+        // Only numbers touching the cell can change, before or after the edit, and only gears
+        // next to those numbers or in the cell itself.
+        let old_numbers = self.numbers_around(row, col);
+        let old_c = std::mem::replace(&mut self.input_data[row][col], c);
+        let new_numbers = self.numbers_around(row, col);
+        self.input_data[row][col] = old_c;
+        let mut gears: Vec<(usize, usize)> = vec![];
+        for (number_row, cols) in old_numbers.iter().chain(&new_numbers).chain([&(row, col..col + 1)]) {
+            for gear_row in number_row - 1..=number_row + 1 {
+                for gear_col in cols.start - 1..=cols.end {
+                    if !gears.contains(&(gear_row, gear_col)) {
+                        gears.push((gear_row, gear_col));
+                    }
+                }
+            }
+        }
+        let gear_ratios = |schematic: &MotorSchematic| -> usize {
+            gears.iter().filter_map(|&(row, col)| gear_ratio(schematic, row, col)).sum()
+        };
+
+        let old_ratios = self.gear_ratio_sum.get().is_some().then(|| gear_ratios(self));
+        let mut old_parts: Vec<usize> = old_numbers
+            .iter()
+            .filter_map(|(row, cols)| self.part_number_index[*row][cols.start])
+            .collect();
+        old_parts.sort();
+        let mut first = old_parts.first().copied().unwrap_or(self.part_numbers.len());
+        for id in old_parts.into_iter().rev() {
+            self.remove_part(id);
+        }
+        self.input_data[row][col] = c;
+        for (row, cols) in new_numbers {
+            let is_part_number = cols.clone().any(|col| has_adjacent(&self.input_data, row, col, |c| self.rules.is_symbol(c)));
+            if is_part_number {
+                let value = self.input_data[row][cols.clone()].iter().fold(0, |value, c| value * 10 + (*c as usize - '0' as usize));
+                first = first.min(self.insert_part(Part { value, row, cols }));
+            }
+        }
+        self.reindex(first);
+        if let Some(old_ratios) = old_ratios {
+            let new_ratios = gear_ratios(self);
+            let sum = self.gear_ratio_sum.get_mut().unwrap();
+            *sum = *sum - old_ratios + new_ratios;
+        }
+    }
+}
+
+/// How `render` marks up the schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// ANSI colours: part numbers green, other numbers red, gears bold yellow and other
+    /// symbols dimmed.
+    Color,
+    /// Brackets, for logs: `[part]`, `{number}` that is not a part, `<gear>`.
+    Plain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Blank,
+    Part(usize),
+    Number,
+    Gear(usize),
+    Symbol,
+}
+
+impl Cell {
+    fn markup(self, style: RenderStyle) -> (&'static str, &'static str) {
+        match (style, self) {
+            (_, Cell::Blank) => ("", ""),
+            (RenderStyle::Color, Cell::Part(_)) => ("\x1b[32m", "\x1b[0m"),
+            (RenderStyle::Color, Cell::Number) => ("\x1b[31m", "\x1b[0m"),
+            (RenderStyle::Color, Cell::Gear(_)) => ("\x1b[1;33m", "\x1b[0m"),
+            (RenderStyle::Color, Cell::Symbol) => ("\x1b[2m", "\x1b[0m"),
+            (RenderStyle::Plain, Cell::Part(_)) => ("[", "]"),
+            (RenderStyle::Plain, Cell::Number) => ("{", "}"),
+            (RenderStyle::Plain, Cell::Gear(_)) => ("<", ">"),
+            (RenderStyle::Plain, Cell::Symbol) => ("", ""),
+        }
+    }
+}
+
+/// Renders the schematic without its border, one line per row.  Rows with gears are followed
+/// by the position and the ratio of each gear, like `  # gear 2:4 ratio 16345`.
+pub fn render(schematic: &MotorSchematic, style: RenderStyle) -> String {
+    let mut output = String::new();
+    let height = schematic.input_data.len();
+    for row in 1..height - 1 {
+        let row_data = &schematic.input_data[row];
+        let mut current = Cell::Blank;
+        let mut gears = vec![];
+        for (col, &c) in row_data.iter().enumerate().take(row_data.len() - 1).skip(1) {
+            let cell = if let Some(id) = schematic.part_number_index[row][col] {
+                Cell::Part(id)
+            } else if c.is_ascii_digit() {
+                Cell::Number
+            } else if let Some(ratio) = gear_ratio(schematic, row, col) {
+                gears.push(format!("gear {row}:{col} ratio {ratio}"));
+                Cell::Gear(col)
+            } else if schematic.rules.is_symbol(c) {
+                Cell::Symbol
+            } else {
+                Cell::Blank
+            };
+            if cell != current {
+                output.push_str(current.markup(style).1);
+                output.push_str(cell.markup(style).0);
+                current = cell;
+            }
+            output.push(c);
+        }
+        output.push_str(current.markup(style).1);
+        if !gears.is_empty() {
+            output.push_str("  # ");
+            output.push_str(&gears.join(", "));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// A symbol in the schematic, in the coordinates of `Part`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// The bipartite graph of symbols and the part numbers next to them.
+#[derive(Debug)]
+pub struct SchematicGraph {
+    pub symbols: Vec<Symbol>,
+    /// (symbol id, part id) for each part next to a symbol, part ids being positions in
+    /// `MotorSchematic::parts`.
+    pub edges: Vec<(usize, usize)>,
+}
+
+pub fn schematic_graph(schematic: &MotorSchematic) -> SchematicGraph {
+    let mut symbols = vec![];
+    let mut edges = vec![];
+    for (row, row_data) in schematic.input_data.iter().enumerate() {
+        for (col, c) in row_data.iter().enumerate() {
+            if schematic.rules.is_symbol(*c) {
+                let mut part_ids: Vec<usize> = adjacent_part_numbers(schematic, row, col).into_iter().collect();
+                part_ids.sort();
+                edges.extend(part_ids.into_iter().map(|part_id| (symbols.len(), part_id)));
+                symbols.push(Symbol { symbol: *c, row, col });
+            }
+        }
+    }
+    SchematicGraph { symbols, edges }
+}
+
+/// A connected group of parts and symbols.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Assembly {
+    pub parts: Vec<usize>,
+    pub symbols: Vec<usize>,
+    /// The sum of the values of the parts.
+    pub value: usize,
+}
+
+/// Returns the connected components of the graph, ordered by their first symbol.  Symbols with
+/// no parts next to them are assemblies of their own.
+pub fn find_assemblies(schematic: &MotorSchematic, graph: &SchematicGraph) -> Vec<Assembly> {
+    let mut symbol_parts = vec![vec![]; graph.symbols.len()];
+    let mut part_symbols = vec![vec![]; schematic.part_numbers.len()];
+    for &(symbol_id, part_id) in &graph.edges {
+        symbol_parts[symbol_id].push(part_id);
+        part_symbols[part_id].push(symbol_id);
+    }
+
+    let mut seen = vec![false; graph.symbols.len()];
+    let mut result = vec![];
+    for first in 0..graph.symbols.len() {
+        if seen[first] {
+            continue;
+        }
+        seen[first] = true;
+        let mut parts: HashSet<usize> = HashSet::new();
+        let mut symbols = vec![];
+        let mut stack = vec![first];
+        while let Some(symbol_id) = stack.pop() {
+            symbols.push(symbol_id);
+            for &part_id in &symbol_parts[symbol_id] {
+                if parts.insert(part_id) {
+                    for &next in &part_symbols[part_id] {
+                        if !seen[next] {
+                            seen[next] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+        let mut parts: Vec<usize> = parts.into_iter().collect();
+        parts.sort();
+        symbols.sort();
+        let value = parts.iter().map(|id| schematic.part_numbers[*id].value).sum();
+        result.push(Assembly { parts, symbols, value });
+    }
+    result
+}
+
+/// Returns the assembly with the most parts, or with the highest value among those.
+pub fn largest_assembly(assemblies: &[Assembly]) -> Option<&Assembly> {
+    assemblies.iter().max_by_key(|assembly| (assembly.parts.len(), assembly.value))
+}
+
+/// Writes the graph in Graphviz DOT format, with parts as boxes labelled with their value.
+pub fn write_dot(schematic: &MotorSchematic, graph: &SchematicGraph, output: &mut dyn Write) -> io::Result<()> {
+    writeln!(output, "graph schematic {{")?;
+    for (id, part) in schematic.part_numbers.iter().enumerate() {
+        writeln!(output, "    p{id} [label=\"{}\", shape=box];", part.value)?;
+    }
+    for (id, symbol) in graph.symbols.iter().enumerate() {
+        writeln!(output, "    s{id} [label={:?}];", symbol.symbol.to_string())?;
+    }
+    for (symbol_id, part_id) in &graph.edges {
+        writeln!(output, "    s{symbol_id} -- p{part_id};")?;
+    }
+    writeln!(output, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_INPUT: &str = "467..114..\n...*......\n..35..633.\n......#...\n617*......\n.....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..";
+    static TEST_INPUT_WIDTH: usize = 10;
+    static TEST_INPUT_HEIGHT: usize = 10;
+
+    #[test]
+    fn test_part_1() {
+        let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+        let schematic = read_motor_schematic(&lines);
+        assert_eq!(schematic.input_data[0].len(), TEST_INPUT_WIDTH + 2);
+        assert_eq!(schematic.input_data.len(), TEST_INPUT_HEIGHT + 2);
+        assert_eq!(find_and_sum_part_numbers(&schematic), 4361);
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 467835)
+    }
+
+    #[test]
+    fn test_rules() {
+        let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+        let read = |rules| read_motor_schematic_with_rules(&lines, rules);
+        let schematic = read(Rules { neighbours: NeighbourCount::AtLeast(1), ..Rules::default() });
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 467835 + 617);
+        let schematic = read(Rules { combine: Combine::Sum, ..Rules::default() });
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 467 + 35 + 755 + 598);
+        let schematic = read(Rules { gears: vec!['*', '#', '$'], neighbours: NeighbourCount::Exactly(1), ..Rules::default() });
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 617 + 633 + 664);
+
+        // With '#' and '$' as blanks, 633 and 664 are no longer part numbers.
+        let schematic = read(Rules { blanks: vec!['.', '#', '$'], ..Rules::default() });
+        assert_eq!(find_and_sum_part_numbers(&schematic), 4361 - 633 - 664);
+
+        let lines: Vec<String> = ["1 2", "x+ ", " 3 "].map(String::from).to_vec();
+        let schematic = read_motor_schematic_with_rules(&lines, Rules { gears: vec!['+'], blanks: vec![' '], ..Rules::default() });
+        assert_eq!(find_and_sum_part_numbers(&schematic), 6);
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 0);
+    }
+
+    #[test]
+    fn test_assemblies() {
+        let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+        let schematic = read_motor_schematic(&lines);
+        let graph = schematic_graph(&schematic);
+        assert_eq!(graph.symbols[0], Symbol { symbol: '*', row: 2, col: 4 });
+        let assemblies = find_assemblies(&schematic, &graph);
+        let values: Vec<usize> = assemblies.iter().map(|assembly| assembly.value).collect();
+        assert_eq!(values, [467 + 35, 633, 617, 592, 664, 755 + 598]);
+        assert_eq!(largest_assembly(&assemblies).unwrap().value, 755 + 598);
+
+        let lines: Vec<String> = ["1*2*3.4", "......#", "5......"].map(String::from).to_vec();
+        let schematic = read_motor_schematic(&lines);
+        let graph = schematic_graph(&schematic);
+        let assemblies = find_assemblies(&schematic, &graph);
+        assert_eq!(
+            assemblies,
+            [
+                Assembly { parts: vec![0, 1, 2], symbols: vec![0, 1], value: 6 },
+                Assembly { parts: vec![3], symbols: vec![2], value: 4 },
+            ]
+        );
+        let mut dot = vec![];
+        write_dot(&schematic, &graph, &mut dot).unwrap();
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            "graph schematic {\n    p0 [label=\"1\", shape=box];\n    p1 [label=\"2\", shape=box];\n    p2 [label=\"3\", shape=box];\n    \
+             p3 [label=\"4\", shape=box];\n    s0 [label=\"*\"];\n    s1 [label=\"*\"];\n    s2 [label=\"#\"];\n    \
+             s0 -- p0;\n    s0 -- p1;\n    s1 -- p1;\n    s1 -- p2;\n    s2 -- p3;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+        let schematic = read_motor_schematic(&lines);
+        assert_eq!(
+            render(&schematic, RenderStyle::Plain),
+            "[467]..{114}..\n\
+             ...<*>......  # gear 2:4 ratio 16345\n\
+             ..[35]..[633].\n\
+             ......#...\n\
+             [617]*......\n\
+             .....+.{58}.\n\
+             ..[592].....\n\
+             ......[755].\n\
+             ...$.<*>....  # gear 9:6 ratio 451490\n\
+             .[664].[598]..\n"
+        );
+
+        let lines: Vec<String> = ["1**2", "+..3"].map(String::from).to_vec();
+        let schematic = read_motor_schematic_with_rules(&lines, Rules { neighbours: NeighbourCount::AtLeast(1), ..Rules::default() });
+        assert_eq!(render(&schematic, RenderStyle::Plain), "[1]<*><*>[2]  # gear 1:2 ratio 1, gear 1:3 ratio 6\n+..[3]\n");
+        assert_eq!(
+            render(&schematic, RenderStyle::Color),
+            "\x1b[32m1\x1b[0m\x1b[1;33m*\x1b[0m\x1b[1;33m*\x1b[0m\x1b[32m2\x1b[0m  # gear 1:2 ratio 1, gear 1:3 ratio 6\n\
+             \x1b[2m+\x1b[0m..\x1b[32m3\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_set_cell() {
+        let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+        let mut schematic = read_motor_schematic(&lines);
+        assert_eq!((schematic.part_sum(), schematic.gear_ratio_sum()), (4361, 467835));
+        schematic.set_cell(1, 9, '#');
+        assert_eq!((schematic.part_sum(), schematic.gear_ratio_sum()), (4361 + 114, 467835));
+        schematic.set_cell(2, 4, '.');
+        assert_eq!((schematic.part_sum(), schematic.gear_ratio_sum()), (4361 + 114 - 467 - 35, 451490));
+        schematic.set_cell(3, 5, '7');
+        assert_eq!(schematic.lines()[2], "..357.633.");
+        schematic.set_cell(3, 6, '1');
+        assert_eq!(schematic.lines()[2], "..3571633.");
+        assert_eq!((schematic.part_sum(), schematic.gear_ratio_sum()), (4361 + 114 - 467 - 35 - 633 + 3571633, 451490));
+    }
+
+    #[test]
+    fn test_edits_match_rebuild() {
+        use crate::property::check;
+
+        let cells = ['0', '1', '5', '9', '.', '.', '.', '*', '*', '#'];
+        check(
+            |rng| rng.vec(0..40, |rng| (rng.range(0..10), rng.range(0..10), *rng.choose(&cells))),
+            |edits: &Vec<(usize, usize, char)>| {
+                let lines: Vec<String> = TEST_INPUT.lines().map(String::from).collect();
+                let rules = Rules { neighbours: NeighbourCount::AtLeast(2), ..Rules::default() };
+                let mut schematic = read_motor_schematic_with_rules(&lines, rules.clone());
+                edits.iter().all(|&(row, col, c)| {
+                    schematic.set_cell(row % 10 + 1, col % 10 + 1, c);
+                    let rebuilt = read_motor_schematic_with_rules(&schematic.lines(), rules.clone());
+                    schematic.parts() == rebuilt.parts()
+                        && schematic.part_number_index == rebuilt.part_number_index
+                        && (schematic.part_sum(), schematic.gear_ratio_sum())
+                            == (find_and_sum_part_numbers(&rebuilt), find_and_sum_gear_ratios(&rebuilt))
+                })
+            },
+        );
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let lines: Vec<String> = ["467..", "..*", "35..12"].map(String::from).to_vec();
+        let read = |ragged| try_read_motor_schematic(&lines, Rules::default(), ragged);
+        assert_eq!(read(RaggedRows::Reject).unwrap_err(), "line 2 is 3 characters long instead of 5 like the first line");
+        let schematic = read(RaggedRows::Pad).unwrap();
+        assert_eq!(schematic.lines(), ["467...", "..*...", "35..12"]);
+        assert_eq!((schematic.part_sum(), schematic.gear_ratio_sum()), (502, 467 * 35));
+
+        let lines: Vec<String> = ["ä*1", "2.3"].map(String::from).to_vec();
+        let schematic = try_read_motor_schematic(&lines, Rules::default(), RaggedRows::Reject).unwrap();
+        assert_eq!(schematic.part_sum(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 3 is 4 characters long")]
+    fn test_ragged_rows_panic() {
+        read_motor_schematic(&["...", "1*2", "...."].map(String::from));
+    }
+
+    #[test]
+    #[should_panic(expected = "blanks must not be digits")]
+    fn test_digit_blanks_panic() {
+        let rules = Rules { blanks: vec!['0', '.'], ..Rules::default() };
+        read_motor_schematic_with_rules(&["10*", "..1"].map(String::from), rules);
+    }
+
+    #[test]
+    #[should_panic(expected = "gears need at least one neighbouring part")]
+    fn test_no_neighbours_panic() {
+        let rules = Rules { neighbours: NeighbourCount::AtLeast(0), ..Rules::default() };
+        read_motor_schematic_with_rules(&["1*.", "..*"].map(String::from), rules);
+    }
+
+    #[test]
+    fn test_empty_schematic() {
+        for lines in [vec![], vec![String::new()], vec![String::new(); 3]] {
+            for ragged in [RaggedRows::Reject, RaggedRows::Pad] {
+                let schematic = try_read_motor_schematic(&lines, Rules::default(), ragged).unwrap();
+                assert_eq!(schematic.lines(), lines);
+                assert_eq!((find_and_sum_part_numbers(&schematic), find_and_sum_gear_ratios(&schematic)), (0, 0));
+                assert_eq!(find_assemblies(&schematic, &schematic_graph(&schematic)), []);
+                assert_eq!(render(&schematic, RenderStyle::Plain), "\n".repeat(lines.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_repeated_numbers() {
+        let lines: Vec<String> = "12.12\n..*..\n.....\n7*7.3".lines().map(String::from).collect();
+        let schematic = read_motor_schematic(&lines);
+        assert_eq!(
+            schematic.parts()[..3],
+            [
+                Part { value: 12, row: 1, cols: 1..3 },
+                Part { value: 12, row: 1, cols: 4..6 },
+                Part { value: 7, row: 4, cols: 1..2 },
+            ]
+        );
+        assert_eq!(schematic.parts().len(), 4);
+        assert_eq!(find_and_sum_part_numbers(&schematic), 38);
+        assert_eq!(find_and_sum_gear_ratios(&schematic), 12 * 12 + 7 * 7);
+    }
+}
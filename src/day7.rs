@@ -0,0 +1,277 @@
+use ::phf::{phf_map, Map};
+use std::cmp::Ordering;
+// use trace::trace;
+
+// trace::init_depth_var!();
+
+pub static CARD_VALUES_1: Map<char, usize> = phf_map! {
+    'A' => 12,
+    'K' => 11,
+    'Q' => 10,
+    'J' => 9,
+    'T' => 8,
+    '9' => 7,
+    '8' => 6,
+    '7' => 5,
+    '6' => 4,
+    '5' => 3,
+    '4' => 2,
+    '3' => 1,
+    '2' => 0,
+};
+
+pub static CARD_VALUES_2: Map<char, usize> = phf_map! {
+    'A' => 12,
+    'K' => 11,
+    'Q' => 10,
+    'T' => 9,
+    '9' => 8,
+    '8' => 7,
+    '7' => 6,
+    '6' => 5,
+    '5' => 4,
+    '4' => 3,
+    '3' => 2,
+    '2' => 1,
+    'J' => 0,
+};
+
+#[derive(Debug)]
+pub(crate) struct Hand {
+    cards: String,
+    pub(crate) card_values: Vec<usize>,
+    pub(crate) value: usize,
+    bid: usize,
+}
+
+pub(crate) fn parse_hand(
+    input: &str,
+    card_value_map: &Map<char, usize>,
+    make_strands: fn(&[usize]) -> Vec<usize>,
+) -> Hand {
+    let splits: Vec<String> = input.split(" ").map(String::from).collect();
+    let card_values: Vec<usize> = splits[0].chars().map(|c| card_value_map[&c]).collect();
+    let value = hand_value(make_strands(&card_values));
+    Hand {
+        cards: splits[0].to_string(),
+        card_values,
+        value,
+        bid: splits.get(1).unwrap_or(&"0".to_string()).parse().unwrap(),
+    }
+}
+
+pub(crate) fn parse_input(
+    input: &str,
+    card_value_map: &Map<char, usize>,
+    make_strands: fn(&[usize]) -> Vec<usize>,
+) -> Vec<Hand> {
+    input
+        .split("\n")
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_hand(s, card_value_map, make_strands))
+        .collect()
+}
+
+pub fn make_strands_1(card_values: &[usize]) -> Vec<usize> {
+    let mut sorted_by_card_value = card_values.to_vec();
+    sorted_by_card_value.sort();
+
+    let mut strands: Vec<usize> = sorted_by_card_value
+        .to_vec()
+        .chunk_by(|&a, &b| a == b)
+        .map(|strand| strand.len())
+        .collect();
+    strands.sort_by(|a, b| b.cmp(a));
+    strands
+}
+
+pub fn make_strands_2(card_values: &[usize]) -> Vec<usize> {
+    let mut sorted_by_card_value = card_values.to_vec();
+    sorted_by_card_value.sort();
+
+    let mut strands_and_lengths: Vec<(usize, usize)> = sorted_by_card_value
+        .to_vec()
+        .chunk_by(|&a, &b| a == b)
+        .map(|strand| (strand[0], strand.len()))
+        .collect();
+    strands_and_lengths.sort_by(|a, b| {
+        // sort joker strand first, then by increasing length
+        if a.0 == 0 {
+            Ordering::Less
+        } else if b.0 == 0 {
+            Ordering::Greater
+        } else {
+            b.1.cmp(&a.1)
+        }
+    });
+    if strands_and_lengths[0].0 == 0 && strands_and_lengths[0].1 < 5 {
+        // merge joker strand with next strand
+        let joker_count = strands_and_lengths[0].1;
+        strands_and_lengths.remove(0);
+        strands_and_lengths[0] = (
+            strands_and_lengths[0].0,
+            strands_and_lengths[0].1 + joker_count,
+        )
+    }
+    let mut strands: Vec<usize> = strands_and_lengths.iter().map(|strand| strand.1).collect();
+    strands.sort_by(|a, b| b.cmp(a));
+    strands
+}
+
+pub(crate) fn hand_value(strands: Vec<usize>) -> usize {
+    match (strands[0], strands.get(1)) {
+        (5, _) => 6,
+        (4, _) => 5,
+        (3, Some(2)) => 4,
+        (3, _) => 3,
+        (2, Some(2)) => 2,
+        (2, _) => 1,
+        _ => 0,
+    }
+}
+
+fn compare_hands(a: &Hand, b: &Hand) -> Ordering {
+    assert_ne!(a.cards.cmp(&b.cards), Ordering::Equal);
+    if a.value > b.value {
+        Ordering::Greater
+    } else if a.value < b.value {
+        Ordering::Less
+    } else {
+        a.card_values
+            .iter()
+            .zip(b.card_values.iter())
+            .map(|(ca, cb)| ca.cmp(cb))
+            .find(|&o| o != Ordering::Equal)
+            .unwrap() // can't have two equal elements in input set, so panic if we do
+    }
+}
+
+pub fn compute_result(
+    input: &str,
+    card_value_map: &Map<char, usize>,
+    make_strands: fn(&[usize]) -> Vec<usize>,
+) -> usize {
+    let mut hands = parse_input(input, card_value_map, make_strands);
+    hands.sort_by(compare_hands);
+
+    hands
+        .iter()
+        .enumerate()
+        .map(|(index, hand)| (index + 1) * hand.bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::check;
+    use crate::rng::Rng;
+
+    static TEST_INPUT: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    fn parse_hand_1(input: &str) -> Hand {
+        parse_hand(input, &CARD_VALUES_1, make_strands_1)
+    }
+
+    #[test]
+    fn test_hand_comparison_1() {
+        assert_eq!(
+            compare_hands(&parse_hand_1("33332"), &parse_hand_1("2AAAA")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_1("77888"), &parse_hand_1("77788")),
+            Ordering::Greater
+        );
+
+        assert_eq!(
+            compare_hands(&parse_hand_1("KK677"), &parse_hand_1("32T3K")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_1("KK677"), &parse_hand_1("KTJJT")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_1("T55J5"), &parse_hand_1("KTJJT")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_1("QQQJA"), &parse_hand_1("T55J5")),
+            Ordering::Greater
+        );
+
+        assert_eq!(
+            compare_hands(&parse_hand_1("T55J5"), &parse_hand_1("QQQJA")),
+            Ordering::Less
+        );
+    }
+
+    fn parse_hand_2(input: &str) -> Hand {
+        parse_hand(input, &CARD_VALUES_2, make_strands_2)
+    }
+
+    #[test]
+    fn test_hand_comparison_2() {
+        assert_eq!(
+            compare_hands(&parse_hand_2("JJJJJ"), &parse_hand_2("22222")),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_2("JKKK2"), &parse_hand_2("QQQQ2")),
+            Ordering::Less
+        );
+
+        assert_eq!(
+            compare_hands(&parse_hand_2("22222"), &parse_hand_2("JJJJJ")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_hands(&parse_hand_2("QQQQ2"), &parse_hand_2("JKKK2")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(
+            compute_result(TEST_INPUT, &CARD_VALUES_1, make_strands_1),
+            6440
+        )
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(
+            compute_result(TEST_INPUT, &CARD_VALUES_2, make_strands_2),
+            5905
+        )
+    }
+
+    const CARDS: &[u8] = b"23456789TJQKA";
+
+    #[test]
+    fn test_compare_hands_antisymmetric() {
+        let hand = |rng: &mut Rng| rng.vec(5..6, |rng| rng.range(0..CARDS.len()));
+        check(
+            |rng| (hand(rng), hand(rng)),
+            |(a, b): &(Vec<usize>, Vec<usize>)| {
+                let valid = |hand: &Vec<usize>| hand.len() == 5 && hand.iter().all(|&card| card < CARDS.len());
+                if !valid(a) || !valid(b) || a == b {
+                    return true;
+                }
+                let cards = |hand: &Vec<usize>| hand.iter().map(|&card| CARDS[card] as char).collect::<String>();
+                [parse_hand_1, parse_hand_2].iter().all(|parse_hand| {
+                    let (a, b) = (parse_hand(&cards(a)), parse_hand(&cards(b)));
+                    compare_hands(&a, &b) == compare_hands(&b, &a).reverse()
+                })
+            },
+        );
+    }
+}
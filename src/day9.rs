@@ -0,0 +1,86 @@
+// use trace::trace;
+
+// trace::init_depth_var!();
+
+pub(crate) type Sequence = Vec<isize>;
+
+pub(crate) fn parse_numbers(input: &str) -> Sequence {
+    input
+        .split_whitespace()
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+fn make_diffs_sequence(s: &Sequence) -> Sequence {
+    s.iter().zip(s.iter().skip(1)).map(|(a, b)| b - a).collect()
+}
+
+pub(crate) fn solve_sequence_1(acc: Vec<Sequence>, this: &Sequence) -> isize {
+    if this.iter().all(|x| *x == 0) {
+        acc.iter().rev().fold(0, |acc, s| acc + s.last().unwrap())
+    } else {
+        solve_sequence_1(
+            acc.iter().chain([this]).cloned().collect(),
+            &make_diffs_sequence(this),
+        )
+    }
+}
+
+pub fn part_1(input: &str) -> isize {
+    input
+        .split("\n")
+        .filter(|s| !s.is_empty())
+        .map(parse_numbers)
+        .map(|s| solve_sequence_1(vec![], &s))
+        .sum()
+}
+
+pub fn part_2(input: &str) -> isize {
+    input
+        .split("\n")
+        .filter(|s| !s.is_empty())
+        .map(parse_numbers)
+        .map(|s| s.into_iter().rev().collect())
+        .map(|s| solve_sequence_1(vec![], &s))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::check;
+
+    static TEST_INPUT: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part_1(TEST_INPUT), 114);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(part_2(TEST_INPUT), 2)
+    }
+
+    fn to_input(sequences: &[Sequence]) -> String {
+        sequences
+            .iter()
+            .map(|sequence| sequence.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(" "))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_part_2_is_part_1_reversed() {
+        check(
+            |rng| rng.vec(1..5, |rng| rng.vec(1..8, |rng| rng.range(0..200) as isize - 100)),
+            |sequences: &Vec<Sequence>| {
+                let reversed: Vec<Sequence> = sequences.iter().map(|s| s.iter().rev().cloned().collect()).collect();
+                part_2(&to_input(sequences)) == part_1(&to_input(&reversed))
+            },
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! C ABI for the day solvers, built into the `cdylib`.  The matching header is `include/aoc.h`.
+//! The build script generates it from this file with cbindgen, and `test_header` fails until the
+//! checked-in copy is updated from `$OUT_DIR/aoc.h`.
+
+use std::cell::Cell;
+use std::ffi::{c_char, CString};
+use std::panic::{self, catch_unwind};
+use std::sync::Once;
+use std::{ptr, slice, str};
+
+use crate::progress;
+
+/// The puzzle was solved and the answer has been stored.
+pub const AOC_OK: i32 = 0;
+/// There is no solver for the requested day and part.
+pub const AOC_UNKNOWN_PUZZLE: i32 = 1;
+/// A required pointer argument was null.
+pub const AOC_NULL_ARGUMENT: i32 = 2;
+/// The input is not valid UTF-8.
+pub const AOC_INVALID_INPUT: i32 = 3;
+/// The solver panicked, usually because the input does not have the expected format.
+pub const AOC_SOLVER_FAILED: i32 = 4;
+
+thread_local! {
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_PANICS: Once = Once::new();
+
+/// Installs, once, a panic hook that keeps panics inside `aoc_solve` off the host's stderr; they
+/// are reported as `AOC_SOLVER_FAILED` instead.  Other panics still go to the previous hook.
+fn quiet_panics() {
+    QUIET_PANICS.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SOLVING.get() {
+                hook(info)
+            }
+        }));
+    });
+}
+
+/// Solves `part` of the puzzle for `day` using the `input_len` bytes at `input`.  On success,
+/// `*answer` is set to a newly allocated, NUL-terminated string that must be released with
+/// `aoc_free_string`.  On failure, `*answer` is set to NULL and an error code is returned.
+/// Nothing is written to stdout or stderr.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes (it may be NULL if `input_len` is zero) and
+/// `answer` must point to writable storage for one pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: u32,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> i32 {
+    if answer.is_null() || (input.is_null() && input_len > 0) {
+        return AOC_NULL_ARGUMENT;
+    }
+    *answer = ptr::null_mut();
+    let bytes = if input_len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, input_len)
+    };
+    let Ok(input) = str::from_utf8(bytes) else {
+        return AOC_INVALID_INPUT;
+    };
+    // The host owns the terminal, so the solvers must not draw progress lines or print panics
+    // on it.
+    quiet_panics();
+    SOLVING.set(true);
+    let result = catch_unwind(|| progress::silenced(|| crate::solve(day as usize, part as usize, input)));
+    SOLVING.set(false);
+    match result {
+        Ok(Some(result)) => {
+            *answer = CString::new(result).unwrap().into_raw();
+            AOC_OK
+        }
+        Ok(None) => AOC_UNKNOWN_PUZZLE,
+        Err(_) => AOC_SOLVER_FAILED,
+    }
+}
+
+/// Releases an answer string returned by `aoc_solve`.  Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `answer` must be NULL or a string returned by `aoc_solve` that has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_string(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn solve(day: u32, part: u32, input: &[u8]) -> (i32, Option<String>) {
+        let mut answer: *mut c_char = ptr::null_mut();
+        unsafe {
+            let status = aoc_solve(day, part, input.as_ptr(), input.len(), &mut answer);
+            if answer.is_null() {
+                (status, None)
+            } else {
+                let result = CStr::from_ptr(answer).to_str().unwrap().to_string();
+                aoc_free_string(answer);
+                (status, Some(result))
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(9, 1, b"0 3 6 9 12 15\n"), (AOC_OK, Some("18".to_string())));
+        assert_eq!(solve(13, 1, b""), (AOC_UNKNOWN_PUZZLE, None));
+        assert_eq!(solve(9, 1, b"\xff\xfe"), (AOC_INVALID_INPUT, None));
+        assert_eq!(solve(2, 1, b"not a game\n"), (AOC_SOLVER_FAILED, None));
+    }
+
+    #[test]
+    fn test_header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/aoc.h"));
+        let checked_in = include_str!("../include/aoc.h");
+        assert!(
+            generated == checked_in,
+            "include/aoc.h is out of date, copy {}/aoc.h over it",
+            env!("OUT_DIR")
+        );
+    }
+
+    #[test]
+    fn test_null_arguments() {
+        unsafe {
+            assert_eq!(aoc_solve(1, 2, ptr::null(), 0, ptr::null_mut()), AOC_NULL_ARGUMENT);
+            let mut answer: *mut c_char = ptr::null_mut();
+            assert_eq!(aoc_solve(1, 2, ptr::null(), 3, &mut answer), AOC_NULL_ARGUMENT);
+            aoc_free_string(ptr::null_mut());
+        }
+    }
+}
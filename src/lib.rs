@@ -0,0 +1,55 @@
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod check;
+pub mod ffi;
+pub mod progress;
+pub mod property;
+pub mod repl;
+pub mod rng;
+pub mod token_scanner;
+
+/// Solves one part of a day's puzzle for the given input, returning `None` if there is no solver
+/// for that day and part.
+pub fn solve(day: usize, part: usize, input: &str) -> Option<String> {
+    let answer = match (day, part) {
+        (1, 1) => day1::sum_calibration_values(input, day1::Mode::Digits).to_string(),
+        (1, 2) => day1::sum_calibration_values(input, day1::Mode::Spelled).to_string(),
+        (2, 1) => day2::part_one(input).to_string(),
+        (2, 2) => day2::part_two(input).to_string(),
+        (3, 1) => day3::find_and_sum_part_numbers(&read_motor_schematic(input)).to_string(),
+        (3, 2) => day3::find_and_sum_gear_ratios(&read_motor_schematic(input)).to_string(),
+        (4, 1) => day4::score_part_1(&day4::read_lottery_cards(input)).to_string(),
+        (4, 2) => day4::score_part_2(&day4::read_lottery_cards(input)).to_string(),
+        (5, 1) => day5::part_1(&day5::parse_input(input)).to_string(),
+        (5, 2) => day5::part_2(&day5::parse_input(input)).to_string(),
+        (6, 1) => day6::part_1(&day6::parse_input_1(input)).to_string(),
+        (6, 2) => day6::ways_to_win_count(&day6::parse_input_2(input)).to_string(),
+        (7, 1) => day7::compute_result(input, &day7::CARD_VALUES_1, day7::make_strands_1).to_string(),
+        (7, 2) => day7::compute_result(input, &day7::CARD_VALUES_2, day7::make_strands_2).to_string(),
+        (8, 1) => day8::count_steps_1(input).to_string(),
+        (8, 2) => day8::count_steps_2(input).to_string(),
+        (9, 1) => day9::part_1(input).to_string(),
+        (9, 2) => day9::part_2(input).to_string(),
+        (10, 1) => day10::part_1(&day10::make_map(input)).to_string(),
+        (10, 2) => day10::part_2(&day10::make_map(input)).to_string(),
+        (11, 1) => day11::shortest_paths_sum(&day11::make_universe(input, 2)).to_string(),
+        (11, 2) => day11::shortest_paths_sum(&day11::make_universe(input, 1000000)).to_string(),
+        (12, 1) => day12::part_1(input).to_string(),
+        _ => return None,
+    };
+    Some(answer)
+}
+
+fn read_motor_schematic(input: &str) -> day3::MotorSchematic {
+    day3::read_motor_schematic(&input.lines().map(String::from).collect::<Vec<String>>())
+}
@@ -0,0 +1,192 @@
+use std::cell::Cell;
+use std::io::{stderr, stdout, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+// How often the progress line is redrawn, and how often (roughly) the clock is looked at to
+// decide whether a redraw is due.  Reading the clock for every item would dominate tight loops,
+// so the number of items between clock reads is derived from the observed throughput.
+const RENDER_INTERVAL: Duration = Duration::from_millis(250);
+const CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+thread_local! {
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with all progress reporters created on this thread disabled, e.g. when solving
+/// through the C ABI on behalf of a host process whose terminal is not ours to draw on.
+pub fn silenced<T>(f: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SILENCED.set(self.0);
+        }
+    }
+    let _restore = Restore(SILENCED.replace(true));
+    f()
+}
+
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: usize,
+    next_check: usize,
+    start: Instant,
+    last_render: Instant,
+    enabled: bool,
+    finished: bool,
+}
+
+impl Progress {
+    /// Creates a progress reporter for a loop over `total` items.  The reporter is silent unless
+    /// stdout is a terminal, so redirected answers are never mixed with progress output, and
+    /// outside of `silenced`.
+    pub fn new(label: &str, total: usize) -> Progress {
+        Progress::with_output(label, total, stdout().is_terminal() && !SILENCED.get())
+    }
+
+    fn with_output(label: &str, total: usize, enabled: bool) -> Progress {
+        let now = Instant::now();
+        Progress {
+            label: label.to_string(),
+            total,
+            done: 0,
+            next_check: if enabled { 1 } else { usize::MAX },
+            start: now,
+            last_render: now,
+            enabled,
+            finished: false,
+        }
+    }
+
+    pub fn inc(&mut self, count: usize) {
+        self.done += count;
+        if self.done >= self.next_check {
+            self.check();
+        }
+    }
+
+    pub fn finish(&mut self) {
+        if self.enabled && !self.finished {
+            self.render(Instant::now());
+            eprintln!();
+        }
+        self.finished = true;
+    }
+
+    fn check(&mut self) {
+        let now = Instant::now();
+        let rate = throughput(self.done, now - self.start);
+        let stride = (rate * CHECK_INTERVAL.as_secs_f64()).max(1.0) as usize;
+        self.next_check = self.done.saturating_add(stride);
+        if now - self.last_render >= RENDER_INTERVAL {
+            self.render(now);
+        }
+    }
+
+    fn render(&mut self, now: Instant) {
+        self.last_render = now;
+        let mut stderr = stderr().lock();
+        let _ = write!(stderr, "\r\x1b[K{}", self.status_line(now - self.start));
+        let _ = stderr.flush();
+    }
+
+    fn status_line(&self, elapsed: Duration) -> String {
+        let rate = throughput(self.done, elapsed);
+        let percent = if self.total > 0 {
+            100.0 * self.done as f64 / self.total as f64
+        } else {
+            100.0
+        };
+        let eta = if rate > 0.0 {
+            format_duration(Duration::from_secs_f64(
+                self.total.saturating_sub(self.done) as f64 / rate,
+            ))
+        } else {
+            "?".to_string()
+        };
+        format!(
+            "{}: {}/{} ({:.1}%) {}/s ETA {}",
+            self.label,
+            self.done,
+            self.total,
+            percent,
+            format_count(rate),
+            eta
+        )
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish()
+    }
+}
+
+fn throughput(done: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        done as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+fn format_count(count: f64) -> String {
+    match count {
+        c if c >= 1e9 => format!("{:.1}G", c / 1e9),
+        c if c >= 1e6 => format!("{:.1}M", c / 1e6),
+        c if c >= 1e3 => format!("{:.1}k", c / 1e3),
+        c => format!("{:.0}", c),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silenced() {
+        assert!(!SILENCED.get());
+        assert!(silenced(|| silenced(|| SILENCED.get()) && SILENCED.get()));
+        assert!(!SILENCED.get());
+        assert!(std::panic::catch_unwind(|| silenced(|| panic!("solver failed"))).is_err());
+        assert!(!SILENCED.get());
+        assert!(!silenced(|| Progress::new("part 2", 10).enabled));
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut progress = Progress::with_output("part 2", 1000, false);
+        progress.inc(250);
+        assert_eq!(
+            progress.status_line(Duration::from_secs(10)),
+            "part 2: 250/1000 (25.0%) 25/s ETA 0:00:30"
+        );
+        progress.inc(750);
+        assert_eq!(
+            progress.status_line(Duration::from_secs(4000)),
+            "part 2: 1000/1000 (100.0%) 0/s ETA 0:00:00"
+        );
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format_count(12.0), "12");
+        assert_eq!(format_count(12345.0), "12.3k");
+        assert_eq!(format_count(2.5e9), "2.5G");
+        assert_eq!(format_duration(Duration::from_secs(3 * 3600 + 7 * 60 + 5)), "3:07:05");
+    }
+
+    #[test]
+    fn test_silent_progress_never_checks_clock() {
+        let mut progress = Progress::with_output("silent", 10, false);
+        progress.inc(10);
+        assert_eq!(progress.next_check, usize::MAX);
+    }
+}
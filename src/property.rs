@@ -0,0 +1,206 @@
+//! A small property-based testing helper: random generators driven by a seeded generator, and
+//! shrinking of failing inputs to a minimal counterexample.
+
+use std::env;
+use std::fmt::Debug;
+
+use crate::rng::Rng;
+
+const DEFAULT_SEED: u64 = 0x2023_1201;
+const DEFAULT_CASES: usize = 200;
+const MAX_SHRINK_STEPS: usize = 10_000;
+
+/// Values that can be simplified when they make a property fail.
+pub trait Shrink: Clone {
+    /// Returns simpler variants of the value, most aggressive simplifications first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_unsigned {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<$t> {
+                let mut candidates = vec![0, self / 2, self.saturating_sub(1)];
+                candidates.dedup();
+                candidates.retain(|candidate| candidate < self);
+                candidates
+            }
+        }
+    )*};
+}
+
+macro_rules! shrink_signed {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<$t> {
+                let mut candidates = vec![0, self.saturating_abs(), self / 2, self - self.signum()];
+                candidates.dedup();
+                // Smaller magnitudes are simpler, and positive values are simpler than negative.
+                candidates.retain(|candidate| (candidate.unsigned_abs(), *candidate < 0) < (self.unsigned_abs(), *self < 0));
+                candidates
+            }
+        }
+    )*};
+}
+
+shrink_unsigned!(u8, u32, u64, usize);
+shrink_signed!(i32, i64, isize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<bool> {
+        if *self {
+            vec![false]
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<char> {
+        if *self == 'a' {
+            vec![]
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<String> {
+        let chars: Vec<char> = self.chars().collect();
+        chars.shrink().into_iter().map(|chars| chars.into_iter().collect()).collect()
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Vec<T>> {
+        let mut candidates = vec![];
+        if !self.is_empty() {
+            candidates.push(vec![]);
+        }
+        // Drop the first or second half, then single elements, then simplify single elements.
+        let half = self.len() / 2;
+        if half > 0 {
+            candidates.push(self[half..].to_vec());
+            candidates.push(self[..half].to_vec());
+        }
+        for index in 0..self.len() {
+            let mut smaller = self.clone();
+            smaller.remove(index);
+            candidates.push(smaller);
+        }
+        for (index, item) in self.iter().enumerate() {
+            for simpler in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[index] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<(A, B)> {
+        let (a, b) = self;
+        let first = a.shrink().into_iter().map(|a| (a, b.clone()));
+        let second = b.shrink().into_iter().map(|b| (a.clone(), b));
+        first.chain(second).collect()
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<(A, B, C)> {
+        let (a, b, c) = self;
+        ((a.clone(), b.clone()), c.clone())
+            .shrink()
+            .into_iter()
+            .map(|((a, b), c)| (a, b, c))
+            .collect()
+    }
+}
+
+/// Repeatedly replaces the failing value with a simpler one that still fails.
+fn shrink_failure<T: Shrink>(mut value: T, property: &impl Fn(&T) -> bool) -> (T, usize) {
+    let mut steps = 0;
+    'shrinking: while steps < MAX_SHRINK_STEPS {
+        for candidate in value.shrink() {
+            if !property(&candidate) {
+                value = candidate;
+                steps += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (value, steps)
+}
+
+/// Checks that `property` holds for values produced by `generate`.  On failure, the input is
+/// shrunk and the test panics with the minimal counterexample found.  Properties with
+/// preconditions should return true for inputs that do not satisfy them, as shrinking can
+/// produce such inputs.
+///
+/// The seed can be changed with the `PROPERTY_SEED` environment variable.
+pub fn check<T, G, P>(generate: G, property: P)
+where
+    T: Shrink + Debug,
+    G: Fn(&mut Rng) -> T,
+    P: Fn(&T) -> bool,
+{
+    let seed = env::var("PROPERTY_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+    check_seeded(seed, DEFAULT_CASES, generate, property)
+}
+
+pub fn check_seeded<T, G, P>(seed: u64, cases: usize, generate: G, property: P)
+where
+    T: Shrink + Debug,
+    G: Fn(&mut Rng) -> T,
+    P: Fn(&T) -> bool,
+{
+    let mut rng = Rng::new(seed);
+    for case in 1..=cases {
+        let value = generate(&mut rng);
+        if !property(&value) {
+            let original = format!("{value:?}");
+            let (minimal, steps) = shrink_failure(value, &property);
+            panic!(
+                "property failed on case {case} with seed {seed}\n\
+                 original input: {original}\n\
+                 minimal counterexample after {steps} shrinks: {minimal:#?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::catch_unwind;
+
+    #[test]
+    fn test_shrinking() {
+        let property = |values: &Vec<usize>| values.iter().sum::<usize>() < 100;
+        let (minimal, _) = shrink_failure(vec![3, 70, 12, 55, 8], &property);
+        assert_eq!(minimal.iter().sum::<usize>(), 100);
+        assert!(minimal.len() <= 2);
+
+        let property = |(a, b): &(isize, isize)| a + b > -10;
+        let (minimal, _) = shrink_failure((-37, 15), &property);
+        assert_eq!(minimal.0 + minimal.1, -10);
+    }
+
+    #[test]
+    fn test_failure_reports_counterexample() {
+        let failure = catch_unwind(|| {
+            check_seeded(1, 100, |rng| rng.vec(0..20, |rng| rng.range(0..1000)), |values| !values.contains(&7))
+        });
+        let message = *failure.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("minimal counterexample"), "{message}");
+        assert!(message.ends_with("[\n    7,\n]"), "{message}");
+    }
+}
@@ -0,0 +1,308 @@
+//! Interactive exploration of a parsed puzzle input, started with `aoc repl <day> [file]`.
+
+use std::fmt::Debug;
+use std::fs::read_to_string;
+use std::io::{self, BufRead, Write};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use itertools::Itertools;
+
+use crate::{day1, day10, day11, day12, day2, day3, day4, day5, day6, day7, day8, day9};
+
+const HELP: &str = "\
+commands:
+  part <n>        solve part n of the puzzle from the input text, parsed again
+  print [<name>]  show a parsed structure, or list the available ones
+  line <n>        show the intermediate result for input line n
+  reload          read and parse the input file again
+  help            show this text
+  quit            leave the REPL";
+
+/// The parsed structures of a day's input, plus a way to look at single input lines for the
+/// days where lines are solved independently of each other.
+struct Parsed {
+    structures: Vec<(&'static str, Box<dyn Debug>)>,
+    explain_line: Option<fn(&str) -> String>,
+}
+
+fn parse(day: usize, input: &str) -> Option<Parsed> {
+    let lines = || input.lines().map(String::from).collect::<Vec<String>>();
+    let parsed = match day {
+        1 => Parsed {
+            structures: vec![("lines", Box::new(lines()))],
+            explain_line: Some(|line| {
+                let value = |mode| match day1::Decoder::new(mode).calibration_value(line) {
+                    Some(value) => value.to_string(),
+                    None => "no digits".to_string(),
+                };
+                format!(
+                    "part 1 calibration value: {}\npart 2 calibration value: {}",
+                    value(day1::Mode::Digits),
+                    value(day1::Mode::Spelled)
+                )
+            }),
+        },
+        2 => Parsed {
+            structures: vec![
+                ("games", Box::new(input.lines().map(day2::parse_game).collect_vec())),
+                ("bag", Box::new(day2::default_bag())),
+            ],
+            explain_line: Some(|line| {
+                let game = day2::parse_game(line);
+                let possible = day2::game_possible(&day2::default_bag(), &game, day2::DrawMode::WithReplacement);
+                let description = format!("{:?}\npossible: {}", game, possible);
+                format!("{description}\npower: {}", day2::power(game))
+            }),
+        },
+        3 => Parsed {
+            structures: vec![("schematic", Box::new(day3::read_motor_schematic(&lines())))],
+            explain_line: None,
+        },
+        4 => Parsed {
+            structures: vec![("cards", Box::new(day4::read_lottery_cards(input)))],
+            explain_line: Some(|line| {
+                let cards = day4::read_lottery_cards(&format!("{line}\n"));
+                format!("match count: {}", day4::card_match_count(&cards[0]))
+            }),
+        },
+        5 => {
+            let parsed_input = day5::parse_input(input);
+            Parsed {
+                structures: vec![
+                    ("seeds", Box::new(parsed_input.seeds.clone())),
+                    ("input", Box::new(parsed_input)),
+                ],
+                explain_line: None,
+            }
+        }
+        6 => Parsed {
+            structures: vec![
+                ("races", Box::new(day6::parse_input_1(input))),
+                ("race", Box::new(day6::parse_input_2(input))),
+            ],
+            explain_line: None,
+        },
+        7 => Parsed {
+            structures: vec![
+                ("hands_1", Box::new(day7::parse_input(input, &day7::CARD_VALUES_1, day7::make_strands_1))),
+                ("hands_2", Box::new(day7::parse_input(input, &day7::CARD_VALUES_2, day7::make_strands_2))),
+            ],
+            explain_line: Some(|line| {
+                let hand_1 = day7::parse_hand(line, &day7::CARD_VALUES_1, day7::make_strands_1);
+                let hand_2 = day7::parse_hand(line, &day7::CARD_VALUES_2, day7::make_strands_2);
+                format!(
+                    "part 1 strands: {:?} value: {}\npart 2 strands: {:?} value: {}",
+                    day7::make_strands_1(&hand_1.card_values),
+                    hand_1.value,
+                    day7::make_strands_2(&hand_2.card_values),
+                    hand_2.value
+                )
+            }),
+        },
+        8 => {
+            let (steps, nodes) = day8::parse_input(input);
+            Parsed {
+                structures: vec![("steps", Box::new(steps)), ("nodes", Box::new(nodes))],
+                explain_line: Some(|line| format!("{:?}", day8::parse_node(line))),
+            }
+        }
+        9 => Parsed {
+            structures: vec![(
+                "sequences",
+                Box::new(input.lines().map(day9::parse_numbers).collect_vec()),
+            )],
+            explain_line: Some(|line| {
+                let sequence = day9::parse_numbers(line);
+                let reversed: day9::Sequence = sequence.iter().rev().cloned().collect();
+                format!(
+                    "next: {}\nprevious: {}",
+                    day9::solve_sequence_1(vec![], &sequence),
+                    day9::solve_sequence_1(vec![], &reversed)
+                )
+            }),
+        },
+        10 => Parsed {
+            structures: vec![("map", Box::new(day10::make_map(input)))],
+            explain_line: None,
+        },
+        11 => {
+            let sorted = |universe: day11::Universe| {
+                universe.into_iter().sorted_by_key(|galaxy| galaxy.number).collect_vec()
+            };
+            Parsed {
+                structures: vec![
+                    ("universe", Box::new(sorted(day11::make_universe(input, 2)))),
+                    ("old_universe", Box::new(sorted(day11::make_universe(input, 1000000)))),
+                ],
+                explain_line: None,
+            }
+        }
+        12 => Parsed {
+            structures: vec![("lines", Box::new(lines()))],
+            explain_line: Some(|line| {
+                format!("arrangements: {}", day12::permutation_match_count(line))
+            }),
+        },
+        _ => return None,
+    };
+    Some(parsed)
+}
+
+pub struct Session {
+    day: usize,
+    filename: String,
+    input: String,
+    parsed: Parsed,
+}
+
+impl Session {
+    pub fn load(day: usize, filename: &str) -> Result<Session, String> {
+        let input = read_to_string(filename).map_err(|error| format!("{filename}: {error}"))?;
+        Session::from_input(day, filename, input)
+    }
+
+    fn from_input(day: usize, filename: &str, input: String) -> Result<Session, String> {
+        // The parsers panic on input they cannot handle, like the solvers in `execute`.
+        let parsed = catch_unwind(|| parse(day, &input))
+            .map_err(|payload| format!("cannot parse {filename}: {}", panic_message(&payload)))?
+            .ok_or(format!("there is no solver for day {day}"))?;
+        Ok(Session {
+            day,
+            filename: filename.to_string(),
+            input,
+            parsed,
+        })
+    }
+
+    /// Executes one command, returning false if the session should end.
+    fn execute(&mut self, command: &str, output: &mut dyn Write) -> io::Result<bool> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words[..] {
+            [] => {}
+            ["quit"] | ["exit"] => return Ok(false),
+            ["help"] => writeln!(output, "{HELP}")?,
+            // The answer comes from `crate::solve`, like through the C ABI, which parses the input
+            // text again rather than using the structures shown by "print".
+            ["part", part] => match part.parse().ok().and_then(|part| crate::solve(self.day, part, &self.input)) {
+                Some(answer) => writeln!(output, "part {part}: {answer}")?,
+                None => writeln!(output, "day {} has no part {part}", self.day)?,
+            },
+            ["print"] => {
+                let names = self.parsed.structures.iter().map(|(name, _)| name).join(", ");
+                writeln!(output, "structures: {names}")?
+            }
+            ["print", name] => match self.parsed.structures.iter().find(|(candidate, _)| *candidate == name) {
+                Some((_, structure)) => writeln!(output, "{structure:#?}")?,
+                None => writeln!(output, "no structure named {name:?}, try \"print\"")?,
+            },
+            ["line", number] => {
+                let line = number
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| self.input.lines().nth(number.checked_sub(1)?));
+                match (line, self.parsed.explain_line) {
+                    (_, None) => writeln!(output, "day {} has no per-line results", self.day)?,
+                    (None, _) => writeln!(output, "no line {number} in {}", self.filename)?,
+                    (Some(line), Some(explain_line)) => {
+                        writeln!(output, "{line}")?;
+                        writeln!(output, "{}", explain_line(line))?
+                    }
+                }
+            }
+            ["reload"] => match Session::load(self.day, &self.filename) {
+                Ok(session) => {
+                    *self = session;
+                    writeln!(output, "reloaded {}", self.filename)?
+                }
+                Err(error) => writeln!(output, "error: {error}")?,
+            },
+            _ => writeln!(output, "unknown command {command:?}, try \"help\"")?,
+        }
+        Ok(true)
+    }
+
+    fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        let prompt = format!("day{}> ", self.day);
+        write!(output, "{prompt}")?;
+        output.flush()?;
+        for command in input.lines() {
+            let command = command?;
+            // Solvers panic on input they cannot handle, which should not end the session.
+            match catch_unwind(AssertUnwindSafe(|| self.execute(&command, &mut output))) {
+                Ok(Ok(true)) => {}
+                Ok(Ok(false)) => return Ok(()),
+                Ok(Err(error)) => return Err(error),
+                Err(payload) => writeln!(output, "error: {}", panic_message(&payload))?,
+            }
+            write!(output, "{prompt}")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload.downcast_ref::<String>().map_or("command failed", String::as_str),
+    }
+}
+
+pub fn run(day: usize, filename: &str) -> Result<(), String> {
+    let mut session = Session::load(day, filename)?;
+    println!("day {day}, input {filename}; \"help\" lists the commands");
+    session
+        .run(io::stdin().lock(), io::stdout())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_INPUT: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+";
+
+    fn transcript(commands: &str) -> String {
+        let mut session = Session::from_input(4, "cards", TEST_INPUT.to_string()).unwrap();
+        let mut output = Vec::new();
+        session.run(commands.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_commands() {
+        let output = transcript("part 1\nline 2\nprint\nprint cards\nline 9\npart 3\nfoo\nquit\nline 1\n");
+        assert!(output.contains("part 1: 12\n"));
+        assert!(output.contains("Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\nmatch count: 2\n"));
+        assert!(output.contains("structures: cards\n"));
+        assert!(output.contains("id: 3,"));
+        assert!(output.contains("no line 9 in cards\n"));
+        assert!(output.contains("day 4 has no part 3\n"));
+        assert!(output.contains("unknown command \"foo\""));
+        assert!(!output.contains("match count: 4"));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let error = Session::from_input(3, "schematic", "12.\n1*\n".to_string()).err().unwrap();
+        assert_eq!(error, "cannot parse schematic: line 2 is 2 characters long instead of 3 like the first line");
+        let mut session = Session::from_input(2, "games", "Game 1: 3 blue\n".to_string()).unwrap();
+        let mut output = Vec::new();
+        session.run("line 1\nquit\n".as_bytes(), &mut output).unwrap();
+        session.input = "Game 1: 3 blue; 4\n".to_string();
+        session.run("line 1\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("possible: true"));
+        assert!(output.contains("error: expected a space at column 18 of \"Game 1: 3 blue; 4\""), "{output}");
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(Session::from_input(13, "input", String::new()).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! A seeded pseudo-random number generator for generating puzzle inputs and test cases.
+
+use std::ops::Range;
+
+/// SplitMix64, which is tiny, fast and good enough for generating test inputs.  The same seed
+/// always produces the same sequence, so failures can be reproduced.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "empty range {range:?}");
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    /// Returns a number between 0 and 1.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.unit() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.range(0..index + 1));
+        }
+    }
+
+    pub fn vec<T>(&mut self, length: Range<usize>, mut generate: impl FnMut(&mut Rng) -> T) -> Vec<T> {
+        let length = self.range(length);
+        (0..length).map(|_| generate(self)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let values: Vec<usize> = (0..100).map(|_| a.range(3..7)).collect();
+        assert!(values.iter().all(|value| (3..7).contains(value)));
+        assert_eq!(values, (0..100).map(|_| b.range(3..7)).collect::<Vec<usize>>());
+        let mut items: Vec<usize> = (0..10).collect();
+        a.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<usize>>());
+    }
+}
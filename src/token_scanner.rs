@@ -0,0 +1,175 @@
+//! Aho-Corasick automaton that finds all occurrences of a set of tokens in one pass over the
+//! input, including overlapping ones like "one" and "eight" in "oneight".
+
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+/// A token found in the input.  `start` and `end` are byte offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenMatch<V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: V,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenScanner<V> {
+    /// Complete transition table, 256 entries per state, so scanning never follows failure links.
+    transitions: Vec<usize>,
+    /// Tokens ending in each state as (length, value), longest first.
+    outputs: Vec<Vec<(usize, V)>>,
+}
+
+impl<V: Copy> TokenScanner<V> {
+    /// Builds the automaton.  If a token is given more than once, the last value wins.
+    pub fn new<'a>(tokens: impl IntoIterator<Item = (&'a str, V)>) -> TokenScanner<V> {
+        let mut transitions = vec![ROOT; 256];
+        let mut outputs: Vec<Vec<(usize, V)>> = vec![vec![]];
+
+        // Build the trie, using ROOT as the "no edge" marker as no edge leads back to the root.
+        for (token, value) in tokens {
+            assert!(!token.is_empty(), "empty token");
+            let mut state = ROOT;
+            for &byte in token.as_bytes() {
+                let next = transitions[state * 256 + byte as usize];
+                state = if next != ROOT {
+                    next
+                } else {
+                    let next = outputs.len();
+                    transitions[state * 256 + byte as usize] = next;
+                    transitions.extend([ROOT; 256]);
+                    outputs.push(vec![]);
+                    next
+                };
+            }
+            outputs[state] = vec![(token.len(), value)];
+        }
+
+        // Breadth first, fill in the missing edges from the failure state and inherit its outputs.
+        let mut failure = vec![ROOT; outputs.len()];
+        let mut queue: VecDeque<usize> = (0..256).map(|byte| transitions[byte]).filter(|&next| next != ROOT).collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[failure[state]].clone();
+            outputs[state].extend(inherited);
+            for byte in 0..256 {
+                let next = transitions[state * 256 + byte];
+                let fallback = transitions[failure[state] * 256 + byte];
+                if next == ROOT {
+                    transitions[state * 256 + byte] = fallback;
+                } else {
+                    failure[next] = fallback;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        TokenScanner { transitions, outputs }
+    }
+
+    /// Returns all tokens in `haystack`, ordered by end position and, for the same end position,
+    /// longest first.
+    pub fn find_iter<'s>(&'s self, haystack: &'s str) -> Matches<'s, V> {
+        Matches {
+            scanner: self,
+            haystack: haystack.as_bytes(),
+            position: 0,
+            state: ROOT,
+            output: 0,
+        }
+    }
+}
+
+pub struct Matches<'s, V> {
+    scanner: &'s TokenScanner<V>,
+    haystack: &'s [u8],
+    /// Offset just behind the last byte consumed.
+    position: usize,
+    state: usize,
+    /// Next output of `state` to report.
+    output: usize,
+}
+
+impl<V: Copy> Iterator for Matches<'_, V> {
+    type Item = TokenMatch<V>;
+
+    fn next(&mut self) -> Option<TokenMatch<V>> {
+        loop {
+            if let Some(&(length, value)) = self.scanner.outputs[self.state].get(self.output) {
+                self.output += 1;
+                return Some(TokenMatch {
+                    start: self.position - length,
+                    end: self.position,
+                    value,
+                });
+            }
+            let &byte = self.haystack.get(self.position)?;
+            self.state = self.scanner.transitions[self.state * 256 + byte as usize];
+            self.position += 1;
+            self.output = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(scanner: &TokenScanner<usize>, haystack: &str) -> Vec<(usize, usize, usize)> {
+        scanner
+            .find_iter(haystack)
+            .map(|found| (found.start, found.end, found.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_overlapping_tokens() {
+        let scanner = TokenScanner::new([("one", 1), ("two", 2), ("eight", 8), ("1", 1)]);
+        assert_eq!(matches(&scanner, "twone"), vec![(0, 3, 2), (2, 5, 1)]);
+        assert_eq!(matches(&scanner, "eightwo1"), vec![(0, 5, 8), (4, 7, 2), (7, 8, 1)]);
+        assert_eq!(matches(&scanner, "xoneight"), vec![(1, 4, 1), (3, 8, 8)]);
+        assert_eq!(matches(&scanner, "on tw"), vec![]);
+        assert_eq!(matches(&scanner, ""), vec![]);
+    }
+
+    #[test]
+    fn test_nested_tokens() {
+        let scanner = TokenScanner::new([("he", 1), ("she", 2), ("hers", 3), ("s", 4)]);
+        assert_eq!(
+            matches(&scanner, "ushers"),
+            vec![(1, 2, 4), (1, 4, 2), (2, 4, 1), (2, 6, 3), (5, 6, 4)]
+        );
+    }
+
+    #[test]
+    fn test_matches_naive_search() {
+        use crate::property::check;
+        use crate::rng::Rng;
+
+        // Tokens and haystacks over a small alphabet, so that there are many overlaps.
+        let word = |rng: &mut Rng, length| rng.vec(length, |rng| *rng.choose(&['a', 'b', 'c'])).into_iter().collect();
+        check(
+            |rng| (rng.vec(1..6, |rng| word(rng, 1..4)), word(rng, 0..30)),
+            |(tokens, haystack): &(Vec<String>, String)| {
+                if tokens.iter().any(String::is_empty) {
+                    return true;
+                }
+                let scanner = TokenScanner::new(tokens.iter().enumerate().map(|(index, token)| (token.as_str(), index)));
+                let mut expected = vec![];
+                for end in 1..=haystack.len() {
+                    let mut ending_here: Vec<(usize, usize, usize)> = vec![];
+                    for (index, token) in tokens.iter().enumerate() {
+                        if haystack[..end].ends_with(token.as_str()) {
+                            // Duplicate tokens report the last value only.
+                            ending_here.retain(|&(start, _, _)| start != end - token.len());
+                            ending_here.push((end - token.len(), end, index));
+                        }
+                    }
+                    ending_here.sort();
+                    expected.extend(ending_here);
+                }
+                matches(&scanner, haystack) == expected
+            },
+        );
+    }
+}
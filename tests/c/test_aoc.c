@@ -0,0 +1,161 @@
+/* Exercises the day solvers through the C ABI using the examples from the puzzle statements. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc.h"
+
+struct example {
+    uint32_t day;
+    uint32_t part;
+    const char *input;
+    const char *expected;
+};
+
+static const char DAY1_1[] = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+
+static const char DAY1_2[] =
+    "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n"
+    "7pqrstsixteen\n";
+
+static const char DAY2[] =
+    "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n"
+    "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n"
+    "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n"
+    "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n"
+    "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green\n";
+
+static const char DAY3[] =
+    "467..114..\n...*......\n..35..633.\n......#...\n617*......\n.....+.58.\n..592.....\n"
+    "......755.\n...$.*....\n.664.598..\n";
+
+static const char DAY4[] =
+    "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n"
+    "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n"
+    "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n"
+    "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n"
+    "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n"
+    "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11\n";
+
+static const char DAY5[] =
+    "seeds: 79 14 55 13\n\n"
+    "seed-to-soil map:\n50 98 2\n52 50 48\n\n"
+    "soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15\n\n"
+    "fertilizer-to-water map:\n49 53 8\n0 11 42\n42 0 7\n57 7 4\n\n"
+    "water-to-light map:\n88 18 7\n18 25 70\n\n"
+    "light-to-temperature map:\n45 77 23\n81 45 19\n68 64 13\n\n"
+    "temperature-to-humidity map:\n0 69 1\n1 0 69\n\n"
+    "humidity-to-location map:\n60 56 37\n56 93 4\n";
+
+static const char DAY6[] = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+static const char DAY7[] = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n";
+
+static const char DAY8_1[] =
+    "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+
+static const char DAY8_2[] =
+    "LR\n\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n22A = (22B, XXX)\n"
+    "22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)\n";
+
+static const char DAY9[] = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n";
+
+static const char DAY10_1[] = "7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ\n";
+
+static const char DAY10_2[] =
+    "...........\n.S-------7.\n.|F-----7|.\n.||.....||.\n.||.....||.\n.|L-7.F-J|.\n"
+    ".|..|.|..|.\n.L--J.L--J.\n...........\n";
+
+static const char DAY11[] =
+    "...#......\n.......#..\n#.........\n..........\n......#...\n.#........\n"
+    ".........#\n..........\n.......#..\n#...#.....\n";
+
+static const char DAY12[] =
+    "???.### 1,1,3\n.??..??...?##. 1,1,3\n?#?#?#?#?#?#?#? 1,3,1,6\n????.#...#... 4,1,1\n"
+    "????.######..#####. 1,6,5\n?###???????? 3,2,1\n";
+
+static const struct example EXAMPLES[] = {
+    {1, 1, DAY1_1, "142"},
+    {1, 2, DAY1_2, "281"},
+    {2, 1, DAY2, "8"},
+    {2, 2, DAY2, "2286"},
+    {3, 1, DAY3, "4361"},
+    {3, 2, DAY3, "467835"},
+    {4, 1, DAY4, "13"},
+    {4, 2, DAY4, "30"},
+    {5, 1, DAY5, "35"},
+    {5, 2, DAY5, "46"},
+    {6, 1, DAY6, "288"},
+    {6, 2, DAY6, "71503"},
+    {7, 1, DAY7, "6440"},
+    {7, 2, DAY7, "5905"},
+    {8, 1, DAY8_1, "6"},
+    {8, 2, DAY8_2, "6"},
+    {9, 1, DAY9, "114"},
+    {9, 2, DAY9, "2"},
+    {10, 1, DAY10_1, "8"},
+    {10, 2, DAY10_2, "4"},
+    {11, 1, DAY11, "374"},
+    {11, 2, DAY11, "82000210"},
+    {12, 1, DAY12, "21"},
+};
+
+static int failures = 0;
+
+static void expect_status(const char *what, int32_t status, int32_t expected) {
+    if (status != expected) {
+        fprintf(stderr, "%s: expected status %d, got %d\n", what, expected, status);
+        failures++;
+    }
+}
+
+static void check_example(const struct example *example) {
+    char *answer = NULL;
+    int32_t status = aoc_solve(example->day, example->part, (const uint8_t *) example->input,
+                               strlen(example->input), &answer);
+    if (status != AOC_OK) {
+        fprintf(stderr, "day %u part %u: status %d\n", example->day, example->part, status);
+        failures++;
+    } else if (strcmp(answer, example->expected) != 0) {
+        fprintf(stderr, "day %u part %u: expected %s, got %s\n", example->day, example->part,
+                example->expected, answer);
+        failures++;
+    } else {
+        printf("day %u part %u: %s\n", example->day, example->part, answer);
+    }
+    aoc_free_string(answer);
+}
+
+static void check_errors(void) {
+    char *answer = (char *) 1;
+    const uint8_t invalid_utf8[] = {0xff, 0xfe};
+    const char *bad_game = "Game one\n";
+
+    expect_status("unknown day", aoc_solve(13, 1, NULL, 0, &answer), AOC_UNKNOWN_PUZZLE);
+    if (answer != NULL) {
+        fprintf(stderr, "unknown day: answer not reset to NULL\n");
+        failures++;
+    }
+    expect_status("unknown part", aoc_solve(12, 2, NULL, 0, &answer), AOC_UNKNOWN_PUZZLE);
+    expect_status("null answer", aoc_solve(1, 2, NULL, 0, NULL), AOC_NULL_ARGUMENT);
+    expect_status("null input", aoc_solve(1, 2, NULL, 5, &answer), AOC_NULL_ARGUMENT);
+    expect_status("invalid utf-8", aoc_solve(9, 1, invalid_utf8, sizeof invalid_utf8, &answer),
+                  AOC_INVALID_INPUT);
+    expect_status("malformed input",
+                  aoc_solve(2, 1, (const uint8_t *) bad_game, strlen(bad_game), &answer),
+                  AOC_SOLVER_FAILED);
+    aoc_free_string(NULL);
+}
+
+int main(void) {
+    for (size_t i = 0; i < sizeof EXAMPLES / sizeof EXAMPLES[0]; i++) {
+        check_example(&EXAMPLES[i]);
+    }
+    check_errors();
+    if (failures > 0) {
+        fprintf(stderr, "%d failures\n", failures);
+        return 1;
+    }
+    printf("all examples passed\n");
+    return 0;
+}
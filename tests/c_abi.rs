@@ -0,0 +1,68 @@
+// Builds tests/c/test_aoc.c against include/aoc.h and the cdylib and runs it.
+
+use std::env;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use advent_of_code::ffi;
+
+fn library_dir() -> PathBuf {
+    // Integration tests live in target/<profile>/deps, next to the cdylib.
+    env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_aoc");
+    let compiled = Command::new(env::var("CC").unwrap_or("cc".to_string()))
+        .arg(manifest_dir.join("tests/c/test_aoc.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg("-ladvent_of_code")
+        .arg("-o")
+        .arg(&executable)
+        .status();
+    match compiled {
+        Ok(status) => assert!(status.success(), "compiling the C test program failed"),
+        Err(error) => {
+            eprintln!("skipping C ABI test, no C compiler available: {error}");
+            return;
+        }
+    }
+    let output = Command::new(&executable)
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .env("DYLD_LIBRARY_PATH", &library_dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    print!("{stdout}");
+    assert!(output.status.success(), "{stderr}");
+    // The library must not write to the host's streams, not even for the failing calls.
+    assert!(stderr.is_empty(), "unexpected output on stderr:\n{stderr}");
+    for line in stdout.lines() {
+        assert!(line.starts_with("day ") || line == "all examples passed", "unexpected output {line:?}");
+    }
+}
+
+#[test]
+fn test_header_constants() {
+    let header = read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("include/aoc.h")).unwrap();
+    for (name, value) in [
+        ("AOC_OK", ffi::AOC_OK),
+        ("AOC_UNKNOWN_PUZZLE", ffi::AOC_UNKNOWN_PUZZLE),
+        ("AOC_NULL_ARGUMENT", ffi::AOC_NULL_ARGUMENT),
+        ("AOC_INVALID_INPUT", ffi::AOC_INVALID_INPUT),
+        ("AOC_SOLVER_FAILED", ffi::AOC_SOLVER_FAILED),
+    ] {
+        assert!(header.contains(&format!("#define {name} {value}\n")), "{name} out of date in aoc.h");
+    }
+    for function in ["int32_t aoc_solve(", "void aoc_free_string("] {
+        assert!(header.contains(function), "{function} missing from aoc.h");
+    }
+}